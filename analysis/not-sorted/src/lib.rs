@@ -82,6 +82,7 @@ where
         broccoli_rayon::build::recurse_par(
             broccoli_rayon::build::SEQ_FALLBACK_DEFAULT,
             &mut NoSorter,
+            &broccoli::build::MedianDivider,
             &mut e,
             v,
         );
@@ -156,8 +157,8 @@ impl<T: Aabb, F: CollisionHandler<T>> NodeHandler<T> for NoSortNodeHandler<F> {
                         handler: self,
                     };
 
-                    g.recurse(this_axis.next(), left.borrow_mut(), true);
-                    g.recurse(this_axis.next(), right.borrow_mut(), false);
+                    g.recurse(left.borrow_mut(), true);
+                    g.recurse(right.borrow_mut(), false);
                 }
             }
         }
@@ -230,10 +231,11 @@ struct InnerRecurser<'a, T, N, C> {
 }
 
 impl<'a, T: Aabb, C: CollisionHandler<T>> InnerRecurser<'a, T, T::Num, C> {
-    fn recurse(&mut self, this_axis: AxisDyn, m: VistrMutPin<Node<T, T::Num>>, is_left: bool) {
+    fn recurse(&mut self, m: VistrMutPin<Node<T, T::Num>>, is_left: bool) {
         let anchor_axis = self.anchor_axis;
 
         let (mut nn, rest) = m.next();
+        let this_axis = nn.axis;
 
         handle_children2(
             &mut self.handler.func,
@@ -252,13 +254,13 @@ impl<'a, T: Aabb, C: CollisionHandler<T>> InnerRecurser<'a, T, T::Num, C> {
                     match is_left {
                         true => {
                             if div < self.anchor.cont.start {
-                                self.recurse(this_axis.next(), right, is_left);
+                                self.recurse(right, is_left);
                                 return;
                             }
                         }
                         false => {
                             if div >= self.anchor.cont.end {
-                                self.recurse(this_axis.next(), left, is_left);
+                                self.recurse(left, is_left);
                                 return;
                             }
                         }
@@ -266,8 +268,8 @@ impl<'a, T: Aabb, C: CollisionHandler<T>> InnerRecurser<'a, T, T::Num, C> {
                 }
             }
 
-            self.recurse(this_axis.next(), left, is_left);
-            self.recurse(this_axis.next(), right, is_left);
+            self.recurse(left, is_left);
+            self.recurse(right, is_left);
         }
     }
 }
@@ -25,6 +25,7 @@ where
         broccoli_rayon::build::recurse_par(
             sss,
             &mut broccoli::build::DefaultSorter,
+            &broccoli::build::MedianDivider,
             &mut e,
             v,
         );
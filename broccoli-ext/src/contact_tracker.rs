@@ -0,0 +1,163 @@
+//!
+//! Turn the colliding pairs of each frame into events for when
+//! contacts begin, persist and end.
+//!
+//! ```rust
+//! use broccoli::{aabb::ManySwappable, rect};
+//! use broccoli_ext::contact_tracker::{ContactEvent, ContactTracker};
+//!
+//! let mut tracker: ContactTracker<usize, usize> = ContactTracker::new();
+//!
+//! for frame in 0..3 {
+//!     let mut aabbs = [
+//!         ManySwappable((rect(0, 10, 0, 10), 0)),
+//!         ManySwappable((rect(5 + frame * 5, 15 + frame * 5, 5, 15), 1)),
+//!     ];
+//!
+//!     broccoli::Tree::new(&mut aabbs).find_colliding_pairs(|a, b| {
+//!         tracker.add(a.0 .1, b.0 .1);
+//!     });
+//!
+//!     tracker.finish_frame(|e| match e {
+//!         ContactEvent::Began(a, b, _) => assert_eq!((frame, a, b), (0, 0, 1)),
+//!         ContactEvent::Persisted(_, _, num) => *num += 1,
+//!         ContactEvent::Ended(a, b, num) => assert_eq!((frame, a, b, num), (2, 0, 1, 1)),
+//!     });
+//! }
+//! assert!(tracker.is_empty());
+//! ```
+//!
+
+///
+/// What happened to a pair of elements this frame.
+///
+#[derive(Debug, PartialEq, Eq)]
+pub enum ContactEvent<'a, K, D> {
+    ///The pair started touching this frame. Its data starts out as the default.
+    Began(K, K, &'a mut D),
+    ///The pair was touching last frame as well.
+    Persisted(K, K, &'a mut D),
+    ///The pair was touching last frame but not this frame.
+    Ended(K, K, D),
+}
+
+///
+/// Remembers which pairs of elements were touching last frame, keyed by
+/// ids that stay the same across frames, along with data for each pair
+/// that carries over for as long as the pair keeps touching.
+///
+/// Every frame, report every colliding pair with [`ContactTracker::add()`],
+/// then call [`ContactTracker::finish_frame()`].
+///
+#[derive(Debug, Clone)]
+pub struct ContactTracker<K, D = ()> {
+    //Sorted by pair.
+    contacts: Vec<((K, K), D)>,
+    pairs: Vec<(K, K)>,
+}
+
+impl<K, D> Default for ContactTracker<K, D> {
+    fn default() -> Self {
+        ContactTracker {
+            contacts: Vec::new(),
+            pairs: Vec::new(),
+        }
+    }
+}
+
+impl<K: Ord + Copy, D: Default> ContactTracker<K, D> {
+    ///
+    /// A tracker with no pairs touching.
+    ///
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Record that the two elements are touching this frame.
+    /// The order of the two does not matter, and adding a pair
+    /// more than once in a frame is the same as adding it once.
+    ///
+    /// Pairs are only buffered here, duplicates included, and are
+    /// sorted and deduplicated by [`ContactTracker::finish_frame()`].
+    /// So a frame uses memory for every call to this function, and
+    /// `finish_frame` has to be called every frame to free it.
+    ///
+    #[inline(always)]
+    pub fn add(&mut self, a: K, b: K) {
+        self.pairs.push(if a <= b { (a, b) } else { (b, a) });
+    }
+
+    ///
+    /// Compare the pairs added this frame with the pairs of last frame
+    /// and produce an event for each. The events are produced in order
+    /// of the pairs, with the smaller id of each pair first.
+    ///
+    pub fn finish_frame(&mut self, mut func: impl FnMut(ContactEvent<K, D>)) {
+        self.pairs.sort_unstable();
+        self.pairs.dedup();
+
+        let mut last = core::mem::take(&mut self.contacts).into_iter().peekable();
+        let mut contacts = Vec::with_capacity(self.pairs.len());
+
+        for pair in self.pairs.drain(..) {
+            while let Some(((a, b), data)) = last.next_if(|(p, _)| *p < pair) {
+                func(ContactEvent::Ended(a, b, data));
+            }
+
+            let data = match last.next_if(|(p, _)| *p == pair) {
+                Some((_, mut data)) => {
+                    func(ContactEvent::Persisted(pair.0, pair.1, &mut data));
+                    data
+                }
+                None => {
+                    let mut data = D::default();
+                    func(ContactEvent::Began(pair.0, pair.1, &mut data));
+                    data
+                }
+            };
+            contacts.push((pair, data));
+        }
+
+        for ((a, b), data) in last {
+            func(ContactEvent::Ended(a, b, data));
+        }
+
+        self.contacts = contacts;
+    }
+
+    ///
+    /// The data of the pair if it was touching as of the last finished frame.
+    ///
+    pub fn get(&self, a: K, b: K) -> Option<&D> {
+        let pair = if a <= b { (a, b) } else { (b, a) };
+        self.contacts
+            .binary_search_by(|(p, _)| p.cmp(&pair))
+            .ok()
+            .map(|i| &self.contacts[i].1)
+    }
+
+    ///
+    /// Every pair touching as of the last finished frame.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (K, K, &D)> {
+        self.contacts.iter().map(|((a, b), d)| (*a, *b, d))
+    }
+
+    ///
+    /// The number of pairs touching as of the last finished frame.
+    ///
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    ///
+    /// True if no pairs were touching as of the last finished frame.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+}
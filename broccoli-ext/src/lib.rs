@@ -2,4 +2,8 @@
 //! Extended query functions
 //!
 
+#[cfg(test)]
+mod tests;
+
 pub mod cacheable_pairs;
+pub mod contact_tracker;
@@ -0,0 +1,92 @@
+use super::contact_tracker::*;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Began(usize, usize),
+    Persisted(usize, usize, usize),
+    Ended(usize, usize, usize),
+}
+
+//Finish the frame with the given pairs. Every pair that persists
+//has its count incremented, so the data shows how long it has lasted.
+fn frame(tracker: &mut ContactTracker<usize, usize>, pairs: &[(usize, usize)]) -> Vec<Event> {
+    for &(a, b) in pairs {
+        tracker.add(a, b);
+    }
+    let mut events = vec![];
+    tracker.finish_frame(|e| match e {
+        ContactEvent::Began(a, b, _) => events.push(Event::Began(a, b)),
+        ContactEvent::Persisted(a, b, num) => {
+            *num += 1;
+            events.push(Event::Persisted(a, b, *num))
+        }
+        ContactEvent::Ended(a, b, num) => events.push(Event::Ended(a, b, num)),
+    });
+    events
+}
+
+#[test]
+fn test_contact_tracker_dedup() {
+    let mut tracker = ContactTracker::new();
+
+    assert_eq!(
+        frame(&mut tracker, &[(0, 1), (0, 1), (2, 3), (0, 1)]),
+        [Event::Began(0, 1), Event::Began(2, 3)]
+    );
+    assert_eq!(tracker.len(), 2);
+
+    assert_eq!(
+        frame(&mut tracker, &[(2, 3), (0, 1), (2, 3)]),
+        [Event::Persisted(0, 1, 1), Event::Persisted(2, 3, 1)]
+    );
+    assert_eq!(tracker.len(), 2);
+}
+
+#[test]
+fn test_contact_tracker_swapped() {
+    let mut tracker = ContactTracker::new();
+
+    assert_eq!(frame(&mut tracker, &[(1, 0), (0, 1)]), [Event::Began(0, 1)]);
+    assert_eq!(frame(&mut tracker, &[(1, 0)]), [Event::Persisted(0, 1, 1)]);
+    assert_eq!(tracker.get(1, 0), Some(&1));
+    assert_eq!(tracker.get(0, 1), Some(&1));
+
+    assert_eq!(frame(&mut tracker, &[]), [Event::Ended(0, 1, 1)]);
+    assert!(tracker.is_empty());
+}
+
+#[test]
+fn test_contact_tracker_data() {
+    let mut tracker = ContactTracker::new();
+
+    assert_eq!(
+        frame(&mut tracker, &[(0, 1), (0, 2)]),
+        [Event::Began(0, 1), Event::Began(0, 2)]
+    );
+    assert_eq!(
+        frame(&mut tracker, &[(0, 1), (0, 2)]),
+        [Event::Persisted(0, 1, 1), Event::Persisted(0, 2, 1)]
+    );
+    assert_eq!(
+        frame(&mut tracker, &[(0, 2)]),
+        [Event::Ended(0, 1, 1), Event::Persisted(0, 2, 2)]
+    );
+    assert_eq!(tracker.iter().collect::<Vec<_>>(), [(0, 2, &2)]);
+
+    assert_eq!(frame(&mut tracker, &[]), [Event::Ended(0, 2, 2)]);
+    assert_eq!(tracker.get(0, 2), None);
+}
+
+#[test]
+fn test_contact_tracker_ends_then_begins() {
+    let mut tracker = ContactTracker::new();
+
+    frame(&mut tracker, &[(0, 1)]);
+    assert_eq!(frame(&mut tracker, &[(0, 1)]), [Event::Persisted(0, 1, 1)]);
+    assert_eq!(frame(&mut tracker, &[]), [Event::Ended(0, 1, 1)]);
+
+    //The data starts over once the pair begins again.
+    assert_eq!(frame(&mut tracker, &[(0, 1)]), [Event::Began(0, 1)]);
+    assert_eq!(tracker.get(0, 1), Some(&0));
+    assert_eq!(frame(&mut tracker, &[(1, 0)]), [Event::Persisted(0, 1, 1)]);
+}
@@ -2,10 +2,11 @@ use broccoli::{
     aabb::Aabb,
     aabb::ManySwap,
     build::TreeEmbryo,
+    indexed::IndexedTree,
     Tree,
     {
-        build::Sorter,
-        build::{DefaultSorter, NodeBuildResult, TreeBuildVisitor},
+        build::{DefaultSorter, IndexedBuildResult, NodeBuildResult, TreeBuildVisitor},
+        build::{DividerStrategy, MedianDivider, Sorter},
     },
 };
 
@@ -35,7 +36,66 @@ where
 
     fn par_new(bots: &'a mut [T]) -> Self {
         let (mut buffer, v) = TreeEmbryo::new(bots);
-        recurse_par(SEQ_FALLBACK_DEFAULT, &mut DefaultSorter, &mut buffer, v);
+        recurse_par(
+            SEQ_FALLBACK_DEFAULT,
+            &mut DefaultSorter,
+            &MedianDivider,
+            &mut buffer,
+            v,
+        );
+        buffer.finish()
+    }
+}
+
+impl<'a, T: Aabb + ManySwap> RayonBuildPar<'a, T> for IndexedTree<'a, T>
+where
+    T: Send,
+    T::Num: Send,
+{
+    fn par_new(bots: &'a mut [T]) -> Self {
+        let mut perm: Vec<_> = (0..bots.len()).collect();
+        let (mut buffer, v) = TreeEmbryo::new(bots);
+        recurse_par_indexed(
+            SEQ_FALLBACK_DEFAULT,
+            &mut buffer,
+            v,
+            &mut perm,
+            &mut Vec::new(),
+        );
+        IndexedTree::from_parts(buffer.finish(), perm)
+    }
+}
+
+///
+/// Build a tree in parallel with a sorter other than the default one.
+///
+pub trait RayonBuildSorterPar<'a, T: Aabb> {
+    ///
+    /// Like [`RayonBuildPar::par_new()`] except the specified sorter
+    /// is used to sort the elements of each node.
+    ///
+    fn par_new_with_sorter<S>(bots: &'a mut [T], sorter: S) -> Self
+    where
+        S: Sorter<T> + Clone + Send;
+}
+
+impl<'a, T: Aabb + ManySwap> RayonBuildSorterPar<'a, T> for Tree<'a, T>
+where
+    T: Send,
+    T::Num: Send,
+{
+    fn par_new_with_sorter<S>(bots: &'a mut [T], mut sorter: S) -> Self
+    where
+        S: Sorter<T> + Clone + Send,
+    {
+        let (mut buffer, v) = TreeEmbryo::new(bots);
+        recurse_par(
+            SEQ_FALLBACK_DEFAULT,
+            &mut sorter,
+            &MedianDivider,
+            &mut buffer,
+            v,
+        );
         buffer.finish()
     }
 }
@@ -52,33 +112,39 @@ pub const SEQ_FALLBACK_DEFAULT: usize = 16;
 // the problem size is big enough such that there
 // are many chunks.
 
-pub fn recurse_par<'a, T: Aabb + ManySwap, S: Sorter<T> + Clone>(
+///
+/// Build the rest of the tree in parallel, using the specified strategy to place
+/// the dividers. [`RayonBuildPar::par_new()`] uses a [`MedianDivider`].
+///
+pub fn recurse_par<'a, T, S, D>(
     num_seq_fallback: usize,
     sorter: &mut S,
+    divider: &D,
     buffer: &mut TreeEmbryo<'a, T, T::Num>,
     vistr: TreeBuildVisitor<'a, T>,
 ) where
-    S: Send,
-    T: Send,
+    T: Aabb + ManySwap + Send,
     T::Num: Send,
+    S: Sorter<T> + Clone + Send,
+    D: DividerStrategy<T> + Sync,
 {
-    let NodeBuildResult { node, rest } = vistr.build_and_next();
+    let NodeBuildResult { node, rest } = vistr.build_and_next_with(divider);
 
     if let Some([left, right]) = rest {
         if node.get_min_elem() <= num_seq_fallback {
             buffer.add(node.finish(sorter));
-            buffer.recurse(left, sorter);
-            buffer.recurse(right, sorter);
+            buffer.recurse_with(left, sorter, divider);
+            buffer.recurse_with(right, sorter, divider);
         } else {
             let mut s2 = sorter.clone();
             let mut b2 = buffer.div();
             rayon::join(
                 || {
                     buffer.add(node.finish(sorter));
-                    recurse_par(num_seq_fallback, sorter, buffer, left);
+                    recurse_par(num_seq_fallback, sorter, divider, buffer, left);
                 },
                 || {
-                    recurse_par(num_seq_fallback, &mut s2, &mut b2, right);
+                    recurse_par(num_seq_fallback, &mut s2, divider, &mut b2, right);
                 },
             );
 
@@ -88,3 +154,49 @@ pub fn recurse_par<'a, T: Aabb + ManySwap, S: Sorter<T> + Clone>(
         buffer.add(node.finish(sorter));
     }
 }
+
+///
+/// Like [`recurse_par`] except every swap of the elements is made to `perm` as well.
+/// See [`TreeBuildVisitor::build_indexed_and_next()`]
+///
+pub fn recurse_par_indexed<'a, T>(
+    num_seq_fallback: usize,
+    buffer: &mut TreeEmbryo<'a, T, T::Num>,
+    vistr: TreeBuildVisitor<'a, T>,
+    perm: &mut [usize],
+    scratch: &mut Vec<usize>,
+) where
+    T: Aabb + ManySwap + Send,
+    T::Num: Send,
+{
+    let IndexedBuildResult { node, rest } = vistr.build_indexed_and_next(perm, scratch);
+
+    if let Some([(left, left_perm), (right, right_perm)]) = rest {
+        if node.min_elem <= num_seq_fallback {
+            buffer.add(node);
+            buffer.recurse_indexed(left, left_perm, scratch);
+            buffer.recurse_indexed(right, right_perm, scratch);
+        } else {
+            let mut b2 = buffer.div();
+            rayon::join(
+                || {
+                    buffer.add(node);
+                    recurse_par_indexed(num_seq_fallback, buffer, left, left_perm, scratch);
+                },
+                || {
+                    recurse_par_indexed(
+                        num_seq_fallback,
+                        &mut b2,
+                        right,
+                        right_perm,
+                        &mut Vec::new(),
+                    );
+                },
+            );
+
+            buffer.combine(b2);
+        }
+    } else {
+        buffer.add(node);
+    }
+}
@@ -5,5 +5,9 @@ pub mod queries;
 
 pub mod prelude {
     pub use super::build::RayonBuildPar;
+    pub use super::build::RayonBuildSorterPar;
+    pub use super::queries::colfind::RayonIndexedQueryPar;
     pub use super::queries::colfind::RayonQueryPar;
+    pub use super::queries::count::RayonCountQueryPar;
+    pub use super::queries::point::RayonPointQueryPar;
 }
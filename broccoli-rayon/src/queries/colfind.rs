@@ -1,10 +1,15 @@
+use core::ops::ControlFlow;
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use broccoli::{
     aabb::pin::AabbPin,
     aabb::Aabb,
+    indexed::IndexedTree,
     queries::colfind::{
         build::{CollisionHandler, CollisionVisitor, NodeHandler},
         oned::DefaultNodeHandler,
     },
+    queries::intersect_with::DualVisitor,
     Tree,
 };
 
@@ -49,6 +54,39 @@ pub trait RayonQueryPar<'a, T: Aabb> {
         Acc: Send,
         T: Send,
         T::Num: Send;
+
+    ///
+    /// Like [`Tree::try_find_colliding_pairs()`] except the pairs are found in parallel.
+    /// Once the closure returns [`ControlFlow::Break`] on one thread, the other threads
+    /// stop soon after. If more than one thread breaks, one of the breaks is returned.
+    ///
+    fn par_try_find_colliding_pairs<B, F>(&mut self, func: F) -> ControlFlow<B>
+    where
+        F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+        F: Send + Clone,
+        B: Send,
+        T: Send,
+        T::Num: Send;
+
+    ///
+    /// Like [`Tree::find_colliding_pairs_with()`] except the pairs are found in parallel.
+    ///
+    fn par_find_colliding_pairs_with<X, F>(&mut self, other: &mut Tree<X>, func: F)
+    where
+        X: Aabb<Num = T::Num>,
+        F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>),
+        F: Send + Clone,
+        T: Send,
+        X: Send,
+        T::Num: Send + Sync;
+
+    ///
+    /// Like [`Tree::count_colliding_pairs()`] except the pairs are found in parallel.
+    ///
+    fn par_count_colliding_pairs(&mut self) -> usize
+    where
+        T: Send,
+        T::Num: Send;
 }
 
 impl<'a, T: Aabb> RayonQueryPar<'a, T> for Tree<'a, T> {
@@ -79,6 +117,14 @@ impl<'a, T: Aabb> RayonQueryPar<'a, T> for Tree<'a, T> {
         f.coll_handler.acc
     }
 
+    fn par_count_colliding_pairs(&mut self) -> usize
+    where
+        T: Send,
+        T::Num: Send,
+    {
+        self.par_find_colliding_pairs_acc_closure(0, |_| 0, |a, b| *a += b, |num, _, _| *num += 1)
+    }
+
     fn par_find_colliding_pairs<F>(&mut self, func: F)
     where
         F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) + Clone,
@@ -91,6 +137,131 @@ impl<'a, T: Aabb> RayonQueryPar<'a, T> for Tree<'a, T> {
         let vv = CollisionVisitor::new(self.vistr_mut());
         recurse_par(vv, &mut f, SEQ_FALLBACK_DEFAULT);
     }
+
+    fn par_try_find_colliding_pairs<B, F>(&mut self, func: F) -> ControlFlow<B>
+    where
+        F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+        F: Send + Clone,
+        B: Send,
+        T: Send,
+        T::Num: Send,
+    {
+        let cancel = AtomicBool::new(false);
+        let mut f = DefaultNodeHandler::new(TryClosureExt {
+            func,
+            res: None,
+            cancel: &cancel,
+        });
+
+        let vv = CollisionVisitor::new(self.vistr_mut());
+        recurse_par(vv, &mut f, SEQ_FALLBACK_DEFAULT);
+
+        match f.coll_handler.res {
+            Some(b) => ControlFlow::Break(b),
+            None => ControlFlow::Continue(()),
+        }
+    }
+
+    fn par_find_colliding_pairs_with<X, F>(&mut self, other: &mut Tree<X>, mut func: F)
+    where
+        X: Aabb<Num = T::Num>,
+        F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>),
+        F: Send + Clone,
+        T: Send,
+        X: Send,
+        T::Num: Send + Sync,
+    {
+        let a_bounds = self.node_bounds();
+        let b_bounds = other.node_bounds();
+
+        let vv = DualVisitor::new(self.vistr_mut(), &a_bounds, other.vistr_mut(), &b_bounds);
+        super::intersect_with::recurse_par(vv, &mut func, SEQ_FALLBACK_DEFAULT);
+    }
+}
+
+pub trait RayonIndexedQueryPar<'a, T: Aabb> {
+    ///
+    /// Like [`IndexedTree::find_colliding_pair_indices()`] except the pairs are found in parallel.
+    ///
+    fn par_find_colliding_pair_indices(&mut self, sorted: bool) -> Vec<(usize, usize)>
+    where
+        T: Send,
+        T::Num: Send;
+}
+
+impl<'a, T: Aabb> RayonIndexedQueryPar<'a, T> for IndexedTree<'a, T> {
+    fn par_find_colliding_pair_indices(&mut self, sorted: bool) -> Vec<(usize, usize)>
+    where
+        T: Send,
+        T::Num: Send,
+    {
+        let (tree, perm) = self.parts_mut();
+        let positions = tree.positions();
+
+        let mut pairs = tree.par_find_colliding_pairs_acc_closure(
+            vec![],
+            |_| vec![],
+            |a, mut b| a.append(&mut b),
+            move |v, a, b| {
+                let (a, b) = (
+                    perm[positions.of(&*a).unwrap()],
+                    perm[positions.of(&*b).unwrap()],
+                );
+                v.push((a.min(b), a.max(b)));
+            },
+        );
+
+        if sorted {
+            pairs.sort_unstable();
+        }
+        pairs
+    }
+}
+
+//Remembers the first break of its thread and tells the other threads to stop.
+struct TryClosureExt<'c, F, B> {
+    func: F,
+    res: Option<B>,
+    cancel: &'c AtomicBool,
+}
+
+impl<'c, T: Aabb, F, B> CollisionHandler<T> for TryClosureExt<'c, F, B>
+where
+    F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+{
+    #[inline(always)]
+    fn collide(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) {
+        if !self.is_finished() {
+            if let ControlFlow::Break(b) = (self.func)(a, b) {
+                self.res = Some(b);
+                self.cancel.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        self.res.is_some() || self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+impl<'c, T: Aabb, F: Clone, B> CollisionHandlerExt<T> for TryClosureExt<'c, F, B>
+where
+    F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+{
+    fn div(&mut self) -> Self {
+        TryClosureExt {
+            func: self.func.clone(),
+            res: None,
+            cancel: self.cancel,
+        }
+    }
+
+    fn add(&mut self, b: Self) {
+        if self.res.is_none() {
+            self.res = b.res;
+        }
+    }
 }
 
 impl<F, T: Aabb> CollisionHandlerExt<T> for F
@@ -161,6 +332,10 @@ pub fn recurse_par<T: Aabb, SO: NodeHandlerExt<T>>(
     T::Num: Send,
     SO: Send,
 {
+    if handler.is_finished() {
+        return;
+    }
+
     if vistr.num_elem() <= num_seq_fallback {
         vistr.recurse_seq(handler);
     } else {
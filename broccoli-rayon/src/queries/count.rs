@@ -0,0 +1,120 @@
+use broccoli::{
+    aabb::Aabb,
+    axgeom::{Rect, Vec2},
+    queries::count::{RadiusCounter, RectCounter},
+    queries::knearest::Knearest,
+    Tree,
+};
+
+use super::colfind::SEQ_FALLBACK_DEFAULT;
+
+pub trait RayonCountQueryPar<'a, T: Aabb> {
+    ///
+    /// Like [`Tree::count_in_rect()`] except subtrees are counted in parallel.
+    ///
+    fn par_count_in_rect(&self, rect: &Rect<T::Num>) -> usize
+    where
+        T: Sync,
+        T::Num: Send + Sync;
+
+    ///
+    /// Like [`Tree::count_intersect_rect()`] except subtrees are counted in parallel.
+    ///
+    fn par_count_intersect_rect(&self, rect: &Rect<T::Num>) -> usize
+    where
+        T: Sync,
+        T::Num: Send + Sync;
+
+    ///
+    /// Like [`Tree::count_in_radius()`] except subtrees are counted in parallel.
+    ///
+    fn par_count_in_radius<K>(&mut self, point: Vec2<T::Num>, radius: T::Num, ktrait: K) -> usize
+    where
+        K: Knearest<T> + Clone + Send,
+        T: Send,
+        T::Num: Send;
+}
+
+impl<'a, T: Aabb> RayonCountQueryPar<'a, T> for Tree<'a, T> {
+    fn par_count_in_rect(&self, rect: &Rect<T::Num>) -> usize
+    where
+        T: Sync,
+        T::Num: Send + Sync,
+    {
+        recurse_par(
+            RectCounter::in_rect(self.get_nodes(), *rect),
+            SEQ_FALLBACK_DEFAULT,
+        )
+    }
+
+    fn par_count_intersect_rect(&self, rect: &Rect<T::Num>) -> usize
+    where
+        T: Sync,
+        T::Num: Send + Sync,
+    {
+        recurse_par(
+            RectCounter::intersect_rect(self.get_nodes(), *rect),
+            SEQ_FALLBACK_DEFAULT,
+        )
+    }
+
+    fn par_count_in_radius<K>(
+        &mut self,
+        point: Vec2<T::Num>,
+        radius: T::Num,
+        mut ktrait: K,
+    ) -> usize
+    where
+        K: Knearest<T> + Clone + Send,
+        T: Send,
+        T::Num: Send,
+    {
+        recurse_par_radius(
+            RadiusCounter::new(self.vistr_mut(), point, radius),
+            &mut ktrait,
+            SEQ_FALLBACK_DEFAULT,
+        )
+    }
+}
+
+pub fn recurse_par<T>(vistr: RectCounter<T>, num_seq_fallback: usize) -> usize
+where
+    T: Aabb + Sync,
+    T::Num: Send + Sync,
+{
+    if vistr.num_elem() <= num_seq_fallback {
+        vistr.count_seq()
+    } else {
+        let (num, rest) = vistr.count_and_next();
+        num + rest.map_or(0, |[left, right]| {
+            let (a, b) = rayon::join(
+                || recurse_par(left, num_seq_fallback),
+                || recurse_par(right, num_seq_fallback),
+            );
+            a + b
+        })
+    }
+}
+
+pub fn recurse_par_radius<T, K>(
+    vistr: RadiusCounter<T>,
+    knear: &mut K,
+    num_seq_fallback: usize,
+) -> usize
+where
+    T: Aabb + Send,
+    T::Num: Send,
+    K: Knearest<T> + Clone + Send,
+{
+    if vistr.num_elem() <= num_seq_fallback {
+        vistr.count_seq(knear)
+    } else {
+        let (num, [left, right]) = vistr.count_and_next(knear);
+        let mut k2 = knear.clone();
+        let (a, b) = rayon::join(
+            || left.map_or(0, |a| recurse_par_radius(a, knear, num_seq_fallback)),
+            || right.map_or(0, |a| recurse_par_radius(a, &mut k2, num_seq_fallback)),
+        );
+        num + a + b
+    }
+}
@@ -0,0 +1,23 @@
+use broccoli::{aabb::pin::AabbPin, aabb::Aabb, queries::intersect_with::DualVisitor};
+
+pub fn recurse_par<T, X, F>(vistr: DualVisitor<T, X>, func: &mut F, num_seq_fallback: usize)
+where
+    X: Aabb<Num = T::Num>,
+    F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>) + Clone + Send,
+    T: Aabb + Send,
+    X: Send,
+    T::Num: Send + Sync,
+{
+    if vistr.num_elem() <= num_seq_fallback {
+        vistr.recurse_seq(func);
+    } else if let Some(mut rest) = vistr.collide_and_next(func) {
+        for crossed in [false, true] {
+            let [a, b] = rest.pairs(crossed);
+            let mut f2 = func.clone();
+            rayon::join(
+                || recurse_par(a, func, num_seq_fallback),
+                || recurse_par(b, &mut f2, num_seq_fallback),
+            );
+        }
+    }
+}
@@ -1 +1,4 @@
 pub mod colfind;
+pub mod count;
+pub mod intersect_with;
+pub mod point;
@@ -0,0 +1,31 @@
+use broccoli::{aabb::Aabb, axgeom::Vec2, Tree};
+use rayon::prelude::*;
+
+pub trait RayonPointQueryPar<'a, T: Aabb> {
+    ///
+    /// Like [`Tree::find_all_containing_point()`] except many points are looked up
+    /// in parallel. The elements containing each point are returned in the order of the points.
+    ///
+    fn par_find_all_containing_points(&self, points: &[Vec2<T::Num>]) -> Vec<Vec<&T>>
+    where
+        T: Sync,
+        T::Num: Sync;
+}
+
+impl<'a, T: Aabb> RayonPointQueryPar<'a, T> for Tree<'a, T> {
+    fn par_find_all_containing_points(&self, points: &[Vec2<T::Num>]) -> Vec<Vec<&T>>
+    where
+        T: Sync,
+        T::Num: Sync,
+    {
+        let tree = self.read_only();
+        points
+            .par_iter()
+            .map(|&point| {
+                let mut found = vec![];
+                tree.find_all_containing_point(point, |a| found.push(a));
+                found
+            })
+            .collect()
+    }
+}
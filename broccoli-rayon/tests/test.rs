@@ -46,3 +46,323 @@ fn test1() {
         }
     }
 }
+
+#[test]
+fn test_par_new_same_as_new() {
+    use broccoli_rayon::build::RayonBuildPar;
+
+    let mut bots: Vec<_> = dists::spiral_iter([400.0, 400.0], 4.0, 1.0)
+        .take(10000)
+        .enumerate()
+        .map(|(i, [x, y])| {
+            let (x, y) = (x as i64, y as i64);
+            (axgeom::rect(x, x + 8, y, y + 8), i)
+        })
+        .collect();
+    let mut bots2 = bots.clone();
+
+    //The parallel build divides the elements the same way the sequential one does.
+    let tree = broccoli::Tree::new(&mut bots);
+    let tree2 = broccoli::Tree::par_new(&mut bots2);
+
+    assert_eq!(tree.get_nodes().len(), tree2.get_nodes().len());
+    for (a, b) in tree.get_nodes().iter().zip(tree2.get_nodes().iter()) {
+        assert_eq!(a.div, b.div);
+        assert_eq!(&*a.range, &*b.range);
+    }
+}
+
+#[test]
+fn test_par_divider() {
+    use broccoli::build::*;
+    use broccoli_rayon::build::*;
+    for &num_bots in [0, 20, 40, 10000].iter() {
+        let s = dists::spiral_iter([400.0, 400.0], 4.0, 1.0);
+
+        let mut bots: Vec<_> = s
+            .take(num_bots)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                (
+                    axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8)),
+                    i,
+                )
+            })
+            .collect();
+
+        let mut bots: Vec<_> = bots.iter_mut().collect();
+
+        let mut vs = vec![];
+        broccoli::Tree::new_with_divider(&mut bots, SahDivider).find_colliding_pairs(|a, b| {
+            vs.push((a.1, b.1));
+        });
+
+        let (mut e, v) = TreeEmbryo::new(&mut bots);
+        recurse_par(
+            SEQ_FALLBACK_DEFAULT,
+            &mut DefaultSorter,
+            &SahDivider,
+            &mut e,
+            v,
+        );
+        let mut tree = e.finish();
+        broccoli::assert::assert_tree_invariants(&tree);
+
+        let mut vs2 = vec![];
+        tree.find_colliding_pairs(|a, b| {
+            vs2.push((a.1, b.1));
+        });
+
+        let mut vs: Vec<_> = vs.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect();
+        let mut vs2: Vec<_> = vs2.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect();
+        vs.sort();
+        vs2.sort();
+        assert_eq!(vs, vs2);
+    }
+}
+
+#[test]
+fn test_par_radix_sorter() {
+    use broccoli::build::RadixSorter;
+    use broccoli_rayon::build::RayonBuildSorterPar;
+    for &num_bots in [0, 20, 40, 10000].iter() {
+        let s = dists::spiral_iter([0.0, 0.0], 4.0, 1.0);
+
+        let mut bots: Vec<_> = s
+            .take(num_bots)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                (
+                    axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8)),
+                    i,
+                )
+            })
+            .collect();
+
+        let mut vs = vec![];
+        broccoli::Tree::new(&mut bots).find_colliding_pairs(|a, b| {
+            vs.push((a.1, b.1));
+        });
+
+        let mut tree = broccoli::Tree::par_new_with_sorter(&mut bots, RadixSorter);
+        broccoli::assert::assert_tree_invariants(&tree);
+
+        let mut vs2 = vec![];
+        tree.find_colliding_pairs(|a, b| {
+            vs2.push((a.1, b.1));
+        });
+
+        let mut vs: Vec<_> = vs.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect();
+        let mut vs2: Vec<_> = vs2.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect();
+        vs.sort();
+        vs2.sort();
+        assert_eq!(vs, vs2);
+    }
+}
+
+#[test]
+fn test_par_colliding_pair_indices() {
+    use broccoli::aabb::ManySwappable;
+    use broccoli::assert::Naive;
+    use broccoli::indexed::IndexedTree;
+    use broccoli_rayon::build::RayonBuildPar;
+    use broccoli_rayon::queries::colfind::RayonIndexedQueryPar;
+    for &num_bots in [0, 20, 40, 10000].iter() {
+        let s = dists::spiral_iter([400.0, 400.0], 4.0, 1.0);
+
+        let mut bots: Vec<_> = s
+            .take(num_bots)
+            .map(|[x, y]| {
+                axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8))
+            })
+            .collect();
+
+        let mut naive: Vec<_> = bots
+            .iter()
+            .enumerate()
+            .map(|(i, r)| ManySwappable((*r, i)))
+            .collect();
+        let mut vs = vec![];
+        Naive::new(&mut naive).find_colliding_pairs(|a, b| {
+            let (a, b) = ((a.0).1, (b.0).1);
+            vs.push((a.min(b), a.max(b)));
+        });
+        vs.sort();
+
+        let mut tree = IndexedTree::par_new(&mut bots);
+        broccoli::assert::assert_tree_invariants(tree.as_tree());
+        assert_eq!(tree.par_find_colliding_pair_indices(true), vs);
+
+        let mut vs2 = tree.find_colliding_pair_indices(false);
+        vs2.sort();
+        assert_eq!(vs2, vs);
+    }
+}
+
+#[test]
+fn test_par_try_find_colliding_pairs() {
+    use broccoli_rayon::queries::colfind::RayonQueryPar;
+    use core::ops::ControlFlow;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    for &num_bots in [0, 20, 40, 10000].iter() {
+        let s = dists::spiral_iter([400.0, 400.0], 4.0, 1.0);
+
+        let mut bots: Vec<_> = s
+            .take(num_bots)
+            .map(|[x, y]| {
+                axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8))
+            })
+            .collect();
+
+        let mut tree = broccoli::Tree::new(&mut bots);
+
+        let mut total = 0;
+        tree.find_colliding_pairs(|_, _| total += 1);
+
+        let num = AtomicUsize::new(0);
+        let res = tree.par_try_find_colliding_pairs(|_, _| {
+            num.fetch_add(1, Ordering::Relaxed);
+            ControlFlow::<()>::Continue(())
+        });
+        assert_eq!(res, ControlFlow::Continue(()));
+        assert_eq!(num.load(Ordering::Relaxed), total);
+
+        let num = AtomicUsize::new(0);
+        let res = tree.par_try_find_colliding_pairs(|_, _| {
+            ControlFlow::Break(num.fetch_add(1, Ordering::Relaxed))
+        });
+        if total == 0 {
+            assert_eq!(res, ControlFlow::Continue(()));
+        } else {
+            assert!(res.is_break());
+            //Every thread stops after the first pair it sees.
+            let num = num.load(Ordering::Relaxed);
+            assert!(num >= 1 && num <= total);
+            if total > 1000 {
+                assert!(num < total);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_par_colliding_pairs_with() {
+    use broccoli::aabb::ManySwappable;
+    use broccoli_rayon::build::RayonBuildPar;
+    use broccoli_rayon::queries::colfind::RayonQueryPar;
+    for &(num_a, num_b) in [(0, 40), (20, 40), (10000, 300), (10000, 10000)].iter() {
+        let mut a: Vec<_> = dists::spiral_iter([400.0, 400.0], 4.0, 1.0)
+            .take(num_a)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                let r =
+                    axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8));
+                ManySwappable((r, i))
+            })
+            .collect();
+
+        let mut b: Vec<_> = dists::spiral_iter([350.0, 420.0], 6.0, 1.5)
+            .take(num_b)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                let r =
+                    axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(5));
+                ManySwappable((r, i))
+            })
+            .collect();
+
+        let mut a = broccoli::Tree::par_new(&mut a);
+        let mut b = broccoli::Tree::par_new(&mut b);
+
+        let mut vs = vec![];
+        a.find_colliding_pairs_with(&mut b, |a, b| vs.push((a.0 .1, b.0 .1)));
+
+        let vs2 = std::sync::Mutex::new(vec![]);
+        a.par_find_colliding_pairs_with(&mut b, |a, b| vs2.lock().unwrap().push((a.0 .1, b.0 .1)));
+        let mut vs2 = vs2.into_inner().unwrap();
+
+        vs.sort();
+        vs2.sort();
+        assert_eq!(vs, vs2);
+    }
+}
+
+#[test]
+fn test_par_find_all_containing_points() {
+    use broccoli_rayon::queries::point::RayonPointQueryPar;
+
+    let mut bots: Vec<_> = dists::spiral_iter([400.0, 400.0], 4.0, 1.0)
+        .take(5000)
+        .map(|[x, y]| {
+            let (x, y) = (x as i64, y as i64);
+            axgeom::rect(x, x + 12, y, y + 12)
+        })
+        .collect();
+
+    let tree = broccoli::Tree::new(&mut bots);
+
+    let points: Vec<_> = (0..1000)
+        .map(|i| axgeom::vec2(300 + i % 200, 300 + i / 5))
+        .collect();
+
+    let res = tree.par_find_all_containing_points(&points);
+    assert_eq!(res.len(), points.len());
+
+    for (&point, found) in points.iter().zip(res) {
+        let mut expected = vec![];
+        tree.read_only()
+            .find_all_containing_point(point, |a| expected.push(a));
+
+        assert_eq!(found.len(), expected.len());
+        assert!(found
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, b)| core::ptr::eq(*a, *b)));
+        assert!(found
+            .iter()
+            .all(|a| a.x.contains(point.x) && a.y.contains(point.y)));
+    }
+}
+
+#[test]
+fn test_par_count() {
+    use broccoli::queries::knearest::AabbKnearest;
+    use broccoli_rayon::prelude::*;
+
+    let mut bots: Vec<_> = dists::spiral_iter([400.0, 400.0], 4.0, 1.0)
+        .take(10000)
+        .map(|[x, y]| {
+            let (x, y) = (x as i64, y as i64);
+            axgeom::rect(x, x + 8, y, y + 8)
+        })
+        .collect();
+
+    let mut tree = broccoli::Tree::new(&mut bots);
+
+    for r in [
+        axgeom::rect(300, 500, 300, 500),
+        axgeom::rect(0, 1000, 0, 1000),
+        axgeom::rect(350, 420, 390, 460),
+    ] {
+        assert_eq!(tree.par_count_in_rect(&r), tree.count_in_rect(&r));
+        assert_eq!(
+            tree.par_count_intersect_rect(&r),
+            tree.count_intersect_rect(&r)
+        );
+    }
+
+    for (point, radius) in [
+        (axgeom::vec2(400, 400), 30 * 30),
+        (axgeom::vec2(0, 0), 500 * 500),
+    ] {
+        assert_eq!(
+            tree.par_count_in_radius(point, radius, AabbKnearest),
+            tree.count_in_radius(point, radius, AabbKnearest)
+        );
+    }
+
+    assert_eq!(
+        tree.par_count_colliding_pairs(),
+        tree.count_colliding_pairs()
+    );
+}
@@ -0,0 +1,32 @@
+use broccoli::owned::OwnedTree;
+use broccoli::rect;
+
+// Unlike `Tree`, an `OwnedTree` has no lifetime,
+// so it can be stored in a struct across frames.
+struct World {
+    tree: OwnedTree<(broccoli::aabb::Rect<i32>, usize)>,
+}
+
+fn main() {
+    let mut world = World {
+        tree: OwnedTree::new(vec![(rect(00, 10, 00, 10), 0), (rect(15, 20, 15, 20), 1)]),
+    };
+
+    let c = world.tree.insert((rect(05, 15, 05, 15), 2));
+
+    let mut acc = [0; 3];
+
+    // The tree is updated lazily on the first query after a modification.
+    world.tree.as_tree().find_colliding_pairs(|a, b| {
+        acc[a.inner().1] += 1;
+        acc[b.inner().1] += 1;
+    });
+
+    assert_eq!(acc, [1, 1, 2]);
+
+    world.tree.remove(c);
+
+    world.tree.as_tree().find_colliding_pairs(|_, _| {
+        unreachable!();
+    });
+}
@@ -0,0 +1,39 @@
+use broccoli::rect;
+fn main() {
+    let mut inner1 = 0;
+    let mut inner2 = 0;
+    let mut inner3 = 0;
+
+    let mut aabbs = [
+        (rect(00, 10, 00, 10), &mut inner1),
+        (rect(15, 20, 15, 20), &mut inner2),
+        (rect(05, 15, 05, 15), &mut inner3),
+    ];
+
+    // Construct tree by doing many swapping of elements
+    let tree = broccoli::Tree::new(&mut aabbs);
+
+    // Store tree data so we can reuse its dividers.
+    let data = tree.get_tree_data();
+
+    // Move the elements a little bit.
+    for a in aabbs.iter_mut() {
+        a.0.x.start += 1;
+        a.0.x.end += 1;
+    }
+
+    // Rebuild the tree reusing the dividers from last time.
+    // Only elements that crossed a divider are moved.
+    let mut tree = broccoli::Tree::refit(&mut aabbs, &data);
+
+    // Find all colliding aabbs.
+    tree.find_colliding_pairs(|a, b| {
+        broccoli::unpack!(a, b);
+        **a += 1;
+        **b += 1;
+    });
+
+    assert_eq!(inner1, 1);
+    assert_eq!(inner2, 1);
+    assert_eq!(inner3, 2);
+}
@@ -46,6 +46,7 @@ impl<T> ManySwap for &mut ManySwappable<T> {}
 
 impl<T: Aabb> Aabb for &mut ManySwappable<T> {
     type Num = T::Num;
+    const INTERSECTION_MODE: IntersectionMode = T::INTERSECTION_MODE;
     #[inline(always)]
     fn get(&self) -> &Rect<Self::Num> {
         self.0.get()
@@ -61,6 +62,7 @@ impl<T: HasInner> HasInner for &mut ManySwappable<T> {
 
 impl<T: Aabb> Aabb for ManySwappable<T> {
     type Num = T::Num;
+    const INTERSECTION_MODE: IntersectionMode = T::INTERSECTION_MODE;
     #[inline(always)]
     fn get(&self) -> &Rect<Self::Num> {
         self.0.get()
@@ -87,9 +89,102 @@ impl<T> Num for T where T: PartialOrd + Copy + Default + std::fmt::Debug {}
 ///
 pub trait Aabb {
     type Num: Num;
+
+    ///Whether this element intersects elements that it only touches.
+    ///Every query checks pairs of elements with the stricter mode of the two.
+    ///Wrap an element in [`HalfOpen`] to change it.
+    const INTERSECTION_MODE: IntersectionMode = IntersectionMode::Closed;
+
     fn get(&self) -> &Rect<Self::Num>;
 }
 
+///
+/// Decides if two ranges that only touch intersect.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IntersectionMode {
+    ///Ranges include their end, so `[0,5]` and `[5,10]` intersect.
+    Closed,
+    ///Ranges exclude their end, so `[0,5)` and `[5,10)` do not intersect.
+    ///This suits tiles on an integer grid that share an edge.
+    HalfOpen,
+}
+
+impl IntersectionMode {
+    ///Half open if either is.
+    #[inline(always)]
+    #[must_use]
+    pub const fn stricter(self, other: Self) -> Self {
+        match (self, other) {
+            (IntersectionMode::Closed, IntersectionMode::Closed) => IntersectionMode::Closed,
+            _ => IntersectionMode::HalfOpen,
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_closed(self) -> bool {
+        matches!(self, IntersectionMode::Closed)
+    }
+
+    ///Returns true if a range that ends at `end` is entirely before
+    ///a range that starts at `start`.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_before<N: PartialOrd>(self, end: N, start: N) -> bool {
+        match self {
+            IntersectionMode::Closed => end < start,
+            IntersectionMode::HalfOpen => end <= start,
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub fn intersects<N: PartialOrd + Copy>(self, a: &Range<N>, b: &Range<N>) -> bool {
+        !self.is_before(a.end, b.start) && !self.is_before(b.end, a.start)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub fn intersects_rect<N: PartialOrd + Copy>(self, a: &Rect<N>, b: &Rect<N>) -> bool {
+        self.intersects(&a.x, &b.x) && self.intersects(&a.y, &b.y)
+    }
+
+    ///Returns true if the rect contains the point.
+    ///Half open rects do not contain the points on their end.
+    #[inline(always)]
+    #[must_use]
+    pub fn contains_point<N: PartialOrd + Copy>(self, a: &Rect<N>, point: Vec2<N>) -> bool {
+        let contains = |r: &Range<N>, p: N| r.start <= p && !self.is_before(r.end, p);
+        contains(&a.x, point.x) && contains(&a.y, point.y)
+    }
+}
+
+///
+/// Wrapper to make an element not intersect the elements that it only touches.
+/// See [`IntersectionMode::HalfOpen`]
+///
+#[derive(Copy, Clone, Debug)]
+pub struct HalfOpen<T>(pub T);
+impl<T: ManySwap> ManySwap for HalfOpen<T> {}
+
+impl<T: Aabb> Aabb for HalfOpen<T> {
+    type Num = T::Num;
+    const INTERSECTION_MODE: IntersectionMode = IntersectionMode::HalfOpen;
+    #[inline(always)]
+    fn get(&self) -> &Rect<Self::Num> {
+        self.0.get()
+    }
+}
+
+impl<T: HasInner> HasInner for HalfOpen<T> {
+    type Inner = T::Inner;
+    #[inline(always)]
+    fn destruct_mut(&mut self) -> (&Rect<Self::Num>, &mut Self::Inner) {
+        self.0.destruct_mut()
+    }
+}
+
 pub(crate) trait AabbExt: Aabb {
     #[inline(always)]
     fn range<A: Axis>(&self, a: A) -> &axgeom::Range<Self::Num> {
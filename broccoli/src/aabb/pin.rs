@@ -125,6 +125,7 @@ impl<T> AabbPin<T> {
 
 /// A destructured [`Node`]
 pub struct NodeRef<'a, T, N> {
+    pub axis: AxisDyn,
     pub div: &'a Option<N>,
     pub cont: &'a Range<N>,
     pub range: AabbPin<&'a mut [T]>,
@@ -135,6 +136,7 @@ impl<'a, 'b: 'a, T, N> AabbPin<&'a mut Node<'b, T, N>> {
     #[inline(always)]
     pub fn into_node_ref(self) -> NodeRef<'a, T, N> {
         NodeRef {
+            axis: self.inner.axis,
             div: &self.inner.div,
             cont: &self.inner.cont,
             range: self.inner.range.borrow_mut(),
@@ -153,6 +155,14 @@ impl<'a, 'b: 'a, T, N> AabbPin<&'a mut Node<'b, T, N>> {
     }
 }
 
+impl<'a, 'b: 'a, T, N> AabbPin<&'a mut crate::tree3d::Node3<'b, T, N>> {
+    /// Return a mutable list of elements in this node.
+    #[inline(always)]
+    pub fn into_range(self) -> AabbPin<&'a mut [T]> {
+        self.inner.range.borrow_mut()
+    }
+}
+
 impl<'a, T: HasInner> AabbPin<&'a mut T> {
     #[inline(always)]
     pub fn destruct_mut(&mut self) -> (&Rect<T::Num>, &mut T::Inner) {
@@ -168,6 +178,14 @@ impl<'a, T: HasInner> AabbPin<&'a mut T> {
     }
 }
 
+impl<'a, T: crate::tree3d::HasInner3> AabbPin<&'a mut T> {
+    /// Like [`AabbPin::unpack_inner()`] except for elements of a [`Tree3`](crate::tree3d::Tree3).
+    #[inline(always)]
+    pub fn unpack_inner3(self) -> &'a mut T::Inner {
+        self.inner.get_inner_mut()
+    }
+}
+
 impl<'a, T> AabbPin<&'a mut [T]> {
     /// Return the element at the specified index.
     /// We can't use the index trait because we don't want
@@ -3,9 +3,11 @@
 //!
 
 use axgeom::Axis;
+use axgeom::AxisDyn;
 use compt::Visitor;
 
 use super::*;
+use tree3d::{Aabb3, Axis3, Node3, Tree3};
 
 pub(crate) fn into_ptr_usize<T>(a: AabbPin<&mut T>) -> usize {
     let a: &T = &a;
@@ -46,14 +48,87 @@ impl<'a, T: Aabb> Assert<'a, T> {
     }
 }
 
+///
+/// Like [`Naive`] except for elements with a 3d bounding box.
+///
+pub struct Naive3<'a, T> {
+    pub(crate) inner: AabbPin<&'a mut [T]>,
+}
+impl<'a, T: Aabb3> Naive3<'a, T> {
+    pub fn new(inner: &'a mut [T]) -> Self {
+        Naive3 {
+            inner: AabbPin::from_mut(inner),
+        }
+    }
+    pub fn from_pinned(inner: AabbPin<&'a mut [T]>) -> Self {
+        Naive3 { inner }
+    }
+
+    pub fn iter_mut(&mut self) -> AabbPinIter<T> {
+        self.inner.borrow_mut().iter_mut()
+    }
+}
+
+///
+/// Compare query results between [`Tree3`] and
+/// the easily verifiable [`Naive3`] versions.
+///
+pub struct Assert3<'a, T> {
+    pub(crate) inner: &'a mut [T],
+}
+impl<'a, T: Aabb3> Assert3<'a, T> {
+    pub fn new(inner: &'a mut [T]) -> Self {
+        Assert3 { inner }
+    }
+}
+
 ///panics if a broken broccoli tree invariant is detected.
 ///For debugging purposes only.
 pub fn assert_tree_invariants<T: Aabb>(tree: &Tree<T>)
 where
     T::Num: core::fmt::Debug,
 {
-    fn inner<A: Axis, T: Aabb>(axis: A, iter: compt::LevelIter<Vistr<Node<T, T::Num>>>)
-    where
+    assert_tree_invariants_inner(tree, true)
+}
+
+///Like [`assert_tree_invariants()`] except the divider of a node does not
+///have to be the start of one of its elements. The dividers of a tree made
+///by [`Tree::refit()`] come from the previous tree and the elements have
+///moved since then, and a [`build::HistogramDivider`] places them between bins.
+pub fn assert_refit_tree_invariants<T: Aabb>(tree: &Tree<T>)
+where
+    T::Num: core::fmt::Debug,
+{
+    assert_tree_invariants_inner(tree, false)
+}
+
+fn assert_tree_invariants_inner<T: Aabb>(tree: &Tree<T>, div_is_a_start: bool)
+where
+    T::Num: core::fmt::Debug,
+{
+    fn inner<T: Aabb>(
+        iter: compt::LevelIter<Vistr<Node<T, T::Num>>>,
+        //The dividers of all the ancestors of this node, and whether
+        //this node is to the left of them or not.
+        constraints: &mut Vec<(AxisDyn, T::Num, bool)>,
+        div_is_a_start: bool,
+    ) where
+        T::Num: core::fmt::Debug,
+    {
+        let ((_depth, nn), rest) = iter.next();
+        match nn.axis {
+            AxisDyn::X => inner_axis(axgeom::XAXIS, nn, rest, constraints, div_is_a_start),
+            AxisDyn::Y => inner_axis(axgeom::YAXIS, nn, rest, constraints, div_is_a_start),
+        }
+    }
+
+    fn inner_axis<A: Axis, T: Aabb>(
+        axis: A,
+        nn: &Node<T, T::Num>,
+        rest: LevelRest<Vistr<Node<T, T::Num>>>,
+        constraints: &mut Vec<(AxisDyn, T::Num, bool)>,
+        div_is_a_start: bool,
+    ) where
         T::Num: core::fmt::Debug,
     {
         fn a_bot_has_value<N: Num>(it: impl Iterator<Item = N>, val: N) -> bool {
@@ -65,7 +140,6 @@ where
             false
         }
 
-        let ((_depth, nn), rest) = iter.next();
         let axis_next = axis.next();
 
         assert!(crate::queries::is_sorted_by(&nn.range, |a, b| a
@@ -73,6 +147,20 @@ where
             .start
             .partial_cmp(&b.range(axis_next).start)));
 
+        for bot in nn.range.iter() {
+            for &(div_axis, div, is_left) in constraints.iter() {
+                let r = match div_axis {
+                    AxisDyn::X => bot.range(axgeom::XAXIS),
+                    AxisDyn::Y => bot.range(axgeom::YAXIS),
+                };
+                if is_left {
+                    assert!(r.end < div);
+                } else {
+                    assert!(r.start > div);
+                }
+            }
+        }
+
         if let Some([start, end]) = rest {
             match nn.div {
                 Some(div) => {
@@ -86,10 +174,12 @@ where
                             assert!(bot.range(axis).contains(div));
                         }
 
-                        assert!(a_bot_has_value(
-                            nn.range.iter().map(|b| b.range(axis).start),
-                            div
-                        ));
+                        if div_is_a_start {
+                            assert!(a_bot_has_value(
+                                nn.range.iter().map(|b| b.range(axis).start),
+                                div
+                            ));
+                        }
 
                         for bot in nn.range.iter() {
                             assert!(cont.contains_range(bot.range(axis)));
@@ -105,8 +195,13 @@ where
                         ));
                     }
 
-                    inner(axis_next, start);
-                    inner(axis_next, end);
+                    constraints.push((axis.to_dyn(), div, true));
+                    inner(start, constraints, div_is_a_start);
+                    constraints.pop();
+
+                    constraints.push((axis.to_dyn(), div, false));
+                    inner(end, constraints, div_is_a_start);
+                    constraints.pop();
                 }
                 None => {
                     for (_depth, n) in start.dfs_preorder_iter().chain(end.dfs_preorder_iter()) {
@@ -123,5 +218,70 @@ where
         }
     }
 
-    inner(default_axis(), tree.vistr().with_depth(compt::Depth(0)))
+    inner(
+        tree.vistr().with_depth(compt::Depth(0)),
+        &mut Vec::new(),
+        div_is_a_start,
+    )
+}
+
+///panics if a broken 3d broccoli tree invariant is detected.
+///For debugging purposes only.
+pub fn assert_tree3_invariants<T: Aabb3>(tree: &Tree3<T>) {
+    fn inner<T: Aabb3>(
+        it: Vistr<Node3<T, T::Num>>,
+        //The dividers of all the ancestors of this node, and whether
+        //this node is to the left of them or not.
+        constraints: &mut Vec<(Axis3, T::Num, bool)>,
+    ) {
+        let (nn, rest) = it.next();
+        let axis = nn.axis;
+        let axis_next = axis.next();
+
+        assert!(crate::queries::is_sorted_by(&nn.range, |a, b| a
+            .get()
+            .get_range(axis_next)
+            .start
+            .partial_cmp(&b.get().get_range(axis_next).start)));
+
+        for bot in nn.range.iter() {
+            for &(div_axis, div, is_left) in constraints.iter() {
+                let r = bot.get().get_range(div_axis);
+                if is_left {
+                    assert!(r.end < div);
+                } else {
+                    assert!(r.start > div);
+                }
+            }
+            if !nn.range.is_empty() {
+                assert!(nn.cont.contains_range(bot.get().get_range(axis)));
+            }
+        }
+
+        if let Some([left, right]) = rest {
+            match nn.div {
+                Some(div) => {
+                    for bot in nn.range.iter() {
+                        assert!(bot.get().get_range(axis).contains(div));
+                    }
+
+                    constraints.push((axis, div, true));
+                    inner(left, constraints);
+                    constraints.pop();
+
+                    constraints.push((axis, div, false));
+                    inner(right, constraints);
+                    constraints.pop();
+                }
+                None => {
+                    for n in left.dfs_preorder_iter().chain(right.dfs_preorder_iter()) {
+                        assert!(n.range.is_empty());
+                        assert!(n.div.is_none());
+                    }
+                }
+            }
+        }
+    }
+
+    inner(tree.vistr(), &mut Vec::new())
 }
@@ -12,6 +12,69 @@ pub const fn default_axis() -> axgeom::XAXIS {
     axgeom::XAXIS
 }
 
+///
+/// Why a tree could not be constructed.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TreeError {
+    /// The aabb at this index has a bound that is not comparable with itself, such as NaN.
+    NanBound { index: usize },
+    /// The aabb at this index has a range whose start is greater than its end.
+    InvertedRange { index: usize },
+    /// The nodes of the tree data hold a different number of elements than were provided.
+    LengthMismatch { expected: usize, found: usize },
+    /// The tree data has this many nodes, which is not the number of nodes of a complete tree.
+    MalformedTreeData { num_nodes: usize },
+}
+
+impl core::fmt::Display for TreeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TreeError::NanBound { index } => {
+                write!(f, "aabb at index {} has a bound that is NaN", index)
+            }
+            TreeError::InvertedRange { index } => {
+                write!(f, "aabb at index {} has a range where start > end", index)
+            }
+            TreeError::LengthMismatch { expected, found } => write!(
+                f,
+                "tree data holds {} elements but {} were provided",
+                expected, found
+            ),
+            TreeError::MalformedTreeData { num_nodes } => write!(
+                f,
+                "tree data has {} nodes which is not the size of a complete tree",
+                num_nodes
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+///
+/// Check that every aabb can be put in a tree.
+/// Returns an error on the first aabb that has a NaN bound or an inverted range.
+///
+pub fn validate_aabbs<T: Aabb>(bots: &[T]) -> Result<(), TreeError> {
+    for (index, a) in bots.iter().enumerate() {
+        let r = a.get();
+        for range in [&r.x, &r.y] {
+            if range.start.partial_cmp(&range.start).is_none()
+                || range.end.partial_cmp(&range.end).is_none()
+            {
+                return Err(TreeError::NanBound { index });
+            }
+        }
+        for range in [&r.x, &r.y] {
+            if range.start > range.end {
+                return Err(TreeError::InvertedRange { index });
+            }
+        }
+    }
+    Ok(())
+}
+
 ///Expose a common Sorter trait so that we may have two version of the tree
 ///where one implementation actually does sort the tree, while the other one
 ///does nothing when sort() is called.
@@ -52,29 +115,31 @@ impl<'a, T: Aabb> NodeFinisher<'a, T> {
         ) -> axgeom::Range<T::Num> {
             let ml = if let Some(ml) = ml { ml } else { middle.len() };
 
-            let Some(start)=middle[0..ml].iter().map(|a|a.range(axis).start).min_by(|a,b|{
-                if a<b{
-                    std::cmp::Ordering::Less
-                }else{
-                    std::cmp::Ordering::Greater
-                }
-            }) else{
-                return Default::default()
+            let Some(start) = middle[0..ml]
+                .iter()
+                .map(|a| a.range(axis).start)
+                .min_by(|a, b| {
+                    if a < b {
+                        std::cmp::Ordering::Less
+                    } else {
+                        std::cmp::Ordering::Greater
+                    }
+                })
+            else {
+                return Default::default();
             };
 
-            let Some(end)=middle.iter().map(|a|a.range(axis).end).max_by(|a,b|{
-                if a>b{
+            let Some(end) = middle.iter().map(|a| a.range(axis).end).max_by(|a, b| {
+                if a > b {
                     std::cmp::Ordering::Greater
-                }else{
+                } else {
                     std::cmp::Ordering::Less
                 }
-            })else{
-                return Default::default()
+            }) else {
+                return Default::default();
             };
 
             axgeom::Range { start, end }
-            
-            
         }
 
         let cont = match self.axis {
@@ -97,6 +162,7 @@ impl<'a, T: Aabb> NodeFinisher<'a, T> {
             //num_elem: self.num_elem,
             range: AabbPin::new(self.mid),
             div: self.div,
+            axis: self.axis,
         }
     }
 }
@@ -108,6 +174,7 @@ pub struct TreeBuildVisitor<'a, T> {
     bots: &'a mut [T],
     current_height: usize,
     axis: AxisDyn,
+    choose_axis: Option<fn(&[T]) -> AxisDyn>,
 }
 
 pub struct NodeBuildResult<'a, T: Aabb> {
@@ -115,6 +182,11 @@ pub struct NodeBuildResult<'a, T: Aabb> {
     pub rest: Option<[TreeBuildVisitor<'a, T>; 2]>,
 }
 
+pub struct IndexedBuildResult<'a, 'p, T: Aabb> {
+    pub node: Node<'a, T, T::Num>,
+    pub rest: Option<[(TreeBuildVisitor<'a, T>, &'p mut [usize]); 2]>,
+}
+
 impl<'a, T: Aabb + ManySwap> TreeBuildVisitor<'a, T> {
     pub fn get_bots(&self) -> &[T] {
         self.bots
@@ -128,14 +200,95 @@ impl<'a, T: Aabb + ManySwap> TreeBuildVisitor<'a, T> {
             bots,
             current_height: num_levels - 1,
             axis: default_axis().to_dyn(),
+            choose_axis: None,
         }
     }
     #[must_use]
     pub fn get_height(&self) -> usize {
         self.current_height
     }
+
+    ///
+    /// Instead of alternating between the x and y axis every level,
+    /// split every node along the axis in which its elements are the most spread out.
+    /// The chosen axis is stored in each [`Node`].
+    ///
+    #[must_use]
+    pub fn split_by_extent(self) -> Self
+    where
+        T::Num: num_traits::ToPrimitive,
+    {
+        TreeBuildVisitor {
+            choose_axis: Some(axis_of_largest_extent),
+            ..self
+        }
+    }
     #[must_use]
     pub fn build_and_next(self) -> NodeBuildResult<'a, T> {
+        self.build_and_next_by(|axis, bots| match axis {
+            AxisDyn::X => construct_non_leaf(axgeom::XAXIS, bots),
+            AxisDyn::Y => construct_non_leaf(axgeom::YAXIS, bots),
+        })
+    }
+
+    ///
+    /// Like [`TreeBuildVisitor::build_and_next()`] except the specified
+    /// strategy decides where to place the divider.
+    ///
+    #[must_use]
+    pub fn build_and_next_with<D: DividerStrategy<T>>(self, divider: &D) -> NodeBuildResult<'a, T> {
+        self.build_and_next_by(|axis, bots| match axis {
+            AxisDyn::X => construct_non_leaf_by(axgeom::XAXIS, bots, divider),
+            AxisDyn::Y => construct_non_leaf_by(axgeom::YAXIS, bots, divider),
+        })
+    }
+
+    ///
+    /// Like [`TreeBuildVisitor::build_and_next()`] except every swap made to the
+    /// elements is made to `perm` as well, and the node is returned already
+    /// finished with its elements sorted like [`DefaultSorter`] does.
+    /// `perm` is split up between the node and its children the same way the
+    /// elements are. `scratch` is only there so that it can be reused from node
+    /// to node instead of allocating.
+    ///
+    /// Panics if `perm` is not as long as the elements.
+    ///
+    #[must_use]
+    pub fn build_indexed_and_next<'p>(
+        self,
+        perm: &'p mut [usize],
+        scratch: &mut Vec<usize>,
+    ) -> IndexedBuildResult<'a, 'p, T> {
+        assert_eq!(perm.len(), self.bots.len());
+
+        let NodeBuildResult { node, rest } = self.build_and_next_by(|axis, bots| match axis {
+            AxisDyn::X => construct_non_leaf_indexed(axgeom::XAXIS, bots, perm, scratch),
+            AxisDyn::Y => construct_non_leaf_indexed(axgeom::YAXIS, bots, perm, scratch),
+        });
+
+        let (node_perm, rest_perm) = perm.split_at_mut(node.mid.len());
+        match node.axis {
+            AxisDyn::X => sort_indexed(axgeom::XAXIS.next(), node.mid, node_perm, scratch),
+            AxisDyn::Y => sort_indexed(axgeom::YAXIS.next(), node.mid, node_perm, scratch),
+        }
+
+        let rest = rest.map(|[left, right]| {
+            let (left_perm, right_perm) = rest_perm.split_at_mut(left.bots.len());
+            [(left, left_perm), (right, right_perm)]
+        });
+
+        IndexedBuildResult {
+            node: node.finish(&mut SortedAlready),
+            rest,
+        }
+    }
+
+    fn build_and_next_by(
+        self,
+        func: impl FnOnce(AxisDyn, &'a mut [T]) -> (NodeFinisher<'a, T>, &'a mut [T], &'a mut [T]),
+    ) -> NodeBuildResult<'a, T> {
+        let choose_axis = self.choose_axis;
+
         //leaf case
         if self.current_height == 0 {
             let node = NodeFinisher {
@@ -149,90 +302,27 @@ impl<'a, T: Aabb + ManySwap> TreeBuildVisitor<'a, T> {
 
             NodeBuildResult { node, rest: None }
         } else {
-            fn construct_non_leaf<T: Aabb>(
-                div_axis: impl Axis,
-                bots: &mut [T],
-            ) -> (NodeFinisher<T>, &mut [T], &mut [T]) {
-                if bots.is_empty() {
-                    return (
-                        NodeFinisher {
-                            middle_left_len: None,
-                            mid: bots,
-                            div: None,
-                            axis: div_axis.to_dyn(),
-                            min_elem: 0,
-                            num_elem: 0,
-                        },
-                        &mut [],
-                        &mut [],
-                    );
-                }
-
-                let med_index = bots.len() / 2;
-
-                let (ll, med, rr) = bots.select_nth_unstable_by(med_index, move |a, b| {
-                    crate::queries::cmp_aabb(div_axis, a, b)
-                });
-
-                let med_val = med.range(div_axis).start;
-
-                let (ml, ll) = partition_left(ll, |a| a.range(div_axis).end >= med_val);
-                let (mr, rr) = partition_left(rr, |a| a.range(div_axis).start <= med_val);
-
-                let ml_len = ml.len();
-                let ll_len = ll.len();
-                let rr_len = rr.len();
-                let mr_len = mr.len();
-
-                //At this point we have:
-                // [ml,ll,mr,rr]
-                //move stuff around so we have:
-                // [ml,mr,ll,rr]
-                {
-                    let (_, rest) = bots.split_at_mut(ml_len);
-                    let (arr, _) = rest.split_at_mut(ll_len + 1 + mr_len);
-                    swap_slice_different_sizes(arr, ll_len)
-                }
-
-                let left_len = ll_len;
-                let right_len = rr_len;
-                let mid_len = ml_len + 1 + mr_len;
-                let middle_left_len = Some(ml_len + 1);
-
-                let (mid, rest) = bots.split_at_mut(mid_len);
-                let (left, right) = rest.split_at_mut(left_len);
-
-                (
-                    NodeFinisher {
-                        middle_left_len,
-                        mid,
-                        div: Some(med_val),
-                        axis: div_axis.to_dyn(),
-                        min_elem: left_len.min(right_len),
-                        num_elem: left_len + right_len,
-                    },
-                    left,
-                    right,
-                )
-            }
-
-            let (finish_node, left, right) = match self.axis {
-                AxisDyn::X => construct_non_leaf(axgeom::XAXIS, self.bots),
-                AxisDyn::Y => construct_non_leaf(axgeom::YAXIS, self.bots),
+            let axis = match choose_axis {
+                Some(f) if !self.bots.is_empty() => f(self.bots),
+                _ => self.axis,
             };
 
+            let (finish_node, left, right) = func(axis, self.bots);
+
             NodeBuildResult {
                 node: finish_node,
                 rest: Some([
                     TreeBuildVisitor {
                         bots: left,
                         current_height: self.current_height.saturating_sub(1),
-                        axis: self.axis.next(),
+                        axis: axis.next(),
+                        choose_axis,
                     },
                     TreeBuildVisitor {
                         bots: right,
                         current_height: self.current_height.saturating_sub(1),
-                        axis: self.axis.next(),
+                        axis: axis.next(),
+                        choose_axis,
                     },
                 ]),
             }
@@ -250,6 +340,105 @@ impl<'a, T: Aabb + ManySwap> TreeBuildVisitor<'a, T> {
     }
 }
 
+///
+/// Like [`TreeBuildVisitor`] except the dividers of a previously built tree
+/// are reused instead of finding new medians.
+///
+pub struct TreeRefitVisitor<'a, 'b, T: Aabb> {
+    bots: &'a mut [T],
+    nodes: &'b [NodeData<T::Num>],
+    current_height: usize,
+    keep_order: bool,
+}
+
+pub struct NodeRefitResult<'a, 'b, T: Aabb> {
+    pub node: NodeFinisher<'a, T>,
+    pub rest: Option<[TreeRefitVisitor<'a, 'b, T>; 2]>,
+}
+
+impl<'a, 'b, T: Aabb + ManySwap> TreeRefitVisitor<'a, 'b, T> {
+    pub fn get_bots(&self) -> &[T] {
+        self.bots
+    }
+
+    #[must_use]
+    pub fn get_height(&self) -> usize {
+        self.current_height
+    }
+
+    ///
+    /// Partition the elements around the dividers without changing the relative
+    /// order of the elements that end up in the same place. If the elements are in
+    /// the order the previous tree left them in, the elements of each node
+    /// will then be nearly sorted already. See [`CoherentSorter`].
+    ///
+    #[must_use]
+    pub fn keep_order(self) -> Self {
+        TreeRefitVisitor {
+            keep_order: true,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn refit_and_next(self) -> NodeRefitResult<'a, 'b, T> {
+        let (data, rest) = self.nodes.split_first().unwrap();
+
+        //leaf case
+        if self.current_height == 0 {
+            let node = NodeFinisher {
+                middle_left_len: None,
+                mid: self.bots,
+                div: None,
+                axis: data.axis,
+                min_elem: 0,
+                num_elem: 0,
+            };
+
+            NodeRefitResult { node, rest: None }
+        } else {
+            let (left_nodes, right_nodes) = rest.split_at(rest.len() / 2);
+
+            //If there was no divider, this part of the tree was empty
+            //last time, so there is nothing to reuse. Find a median instead.
+            let (finish_node, left, right) = match (data.axis, data.div) {
+                (AxisDyn::X, Some(div)) if self.keep_order => {
+                    construct_non_leaf_with_div_stable(axgeom::XAXIS, div, self.bots)
+                }
+                (AxisDyn::Y, Some(div)) if self.keep_order => {
+                    construct_non_leaf_with_div_stable(axgeom::YAXIS, div, self.bots)
+                }
+                (AxisDyn::X, Some(div)) => {
+                    construct_non_leaf_with_div(axgeom::XAXIS, div, self.bots)
+                }
+                (AxisDyn::Y, Some(div)) => {
+                    construct_non_leaf_with_div(axgeom::YAXIS, div, self.bots)
+                }
+                (AxisDyn::X, None) => construct_non_leaf(axgeom::XAXIS, self.bots),
+                (AxisDyn::Y, None) => construct_non_leaf(axgeom::YAXIS, self.bots),
+            };
+
+            NodeRefitResult {
+                node: finish_node,
+                rest: Some([
+                    TreeRefitVisitor {
+                        bots: left,
+                        nodes: left_nodes,
+                        current_height: self.current_height - 1,
+                        keep_order: self.keep_order,
+                    },
+                    TreeRefitVisitor {
+                        bots: right,
+                        nodes: right_nodes,
+                        current_height: self.current_height - 1,
+                        keep_order: self.keep_order,
+                    },
+                ]),
+            }
+        }
+    }
+}
+
 pub struct TreeEmbryo<'a, T, N> {
     total_num_nodes: usize,
     target_num_nodes: usize,
@@ -260,6 +449,14 @@ impl<'a, T: Aabb> TreeEmbryo<'a, T, T::Num> {
         let num_level = num_level::default(bots.len());
         Self::with_num_level(bots, num_level)
     }
+    ///
+    /// Like [`TreeEmbryo::new()`] except the aabbs are checked first.
+    /// See [`validate_aabbs()`]
+    ///
+    pub fn try_new(bots: &'a mut [T]) -> Result<(Self, TreeBuildVisitor<'a, T>), TreeError> {
+        validate_aabbs(bots)?;
+        Ok(Self::new(bots))
+    }
     pub fn with_num_level(
         bots: &'a mut [T],
         num_levels: usize,
@@ -269,6 +466,7 @@ impl<'a, T: Aabb> TreeEmbryo<'a, T, T::Num> {
             bots,
             current_height: num_levels - 1,
             axis: default_axis().to_dyn(),
+            choose_axis: None,
         };
 
         //Minus 1 because the embryo might be split.
@@ -285,6 +483,77 @@ impl<'a, T: Aabb> TreeEmbryo<'a, T, T::Num> {
             v,
         )
     }
+    ///
+    /// Start building a tree that reuses the dividers of the specified tree data.
+    /// See [`Tree::refit()`]
+    ///
+    /// Panics if the tree data does not have the number of nodes of a complete tree.
+    /// Tree data from [`Tree::get_tree_data()`] always does.
+    ///
+    pub fn with_tree_data<'b>(
+        bots: &'a mut [T],
+        data: &'b TreeData<T::Num>,
+    ) -> (TreeEmbryo<'a, T, T::Num>, TreeRefitVisitor<'a, 'b, T>) {
+        let num_levels = compt::dfs_order::CompleteTree::from_preorder(&data.nodes)
+            .unwrap()
+            .get_height();
+
+        let (embryo, v) = Self::with_num_level(bots, num_levels);
+        (
+            embryo,
+            TreeRefitVisitor {
+                bots: v.bots,
+                nodes: &data.nodes,
+                current_height: v.current_height,
+                keep_order: false,
+            },
+        )
+    }
+
+    ///
+    /// Like [`TreeEmbryo::with_tree_data()`] except the elements are first put back
+    /// in the order that the tree the data came from left them in, and keep that
+    /// order wherever they can while being refitted. See [`Tree::refit_coherent()`]
+    ///
+    /// This is for when `bots` is rebuilt each frame in an order of its own, such as
+    /// the order of the entities it is made from. `order` holds, for each position in
+    /// the previous tree, the index into `bots` of the element that was there.
+    /// See [`indexed::IndexedTree::get_permutation()`]
+    ///
+    /// Panics if `order` is not a permutation of the indices of `bots`.
+    ///
+    pub fn with_previous_order<'b>(
+        bots: &'a mut [T],
+        data: &'b TreeData<T::Num>,
+        order: &[usize],
+    ) -> (TreeEmbryo<'a, T, T::Num>, TreeRefitVisitor<'a, 'b, T>)
+    where
+        T: ManySwap,
+    {
+        assert_eq!(order.len(), bots.len(), "order has the wrong length");
+
+        //Where each element has to go. Filling it in also checks that every
+        //index is in range and shows up only once.
+        let mut dest = vec![usize::MAX; order.len()];
+        for (pos, &i) in order.iter().enumerate() {
+            assert!(
+                i < dest.len() && dest[i] == usize::MAX,
+                "order is not a permutation"
+            );
+            dest[i] = pos;
+        }
+        for i in 0..dest.len() {
+            while dest[i] != i {
+                let j = dest[i];
+                bots.swap(i, j);
+                dest.swap(i, j);
+            }
+        }
+
+        let (embryo, v) = Self::with_tree_data(bots, data);
+        (embryo, v.keep_order())
+    }
+
     pub fn add(&mut self, node: Node<'a, T, T::Num>) {
         self.nodes.push(node);
     }
@@ -329,6 +598,54 @@ impl<'a, T: Aabb> TreeEmbryo<'a, T, T::Num> {
             self.recurse(right, sorter);
         }
     }
+
+    /// Recurse sequentially using the specified strategy to place dividers
+    pub fn recurse_with<S: Sorter<T>, D: DividerStrategy<T>>(
+        &mut self,
+        a: TreeBuildVisitor<'a, T>,
+        sorter: &mut S,
+        divider: &D,
+    ) where
+        T: ManySwap,
+    {
+        let NodeBuildResult { node, rest } = a.build_and_next_with(divider);
+        self.add(node.finish(sorter));
+        if let Some([left, right]) = rest {
+            self.recurse_with(left, sorter, divider);
+            self.recurse_with(right, sorter, divider);
+        }
+    }
+
+    /// Recurse sequentially, making every swap of the elements to `perm` as well.
+    /// See [`TreeBuildVisitor::build_indexed_and_next()`]
+    pub fn recurse_indexed(
+        &mut self,
+        a: TreeBuildVisitor<'a, T>,
+        perm: &mut [usize],
+        scratch: &mut Vec<usize>,
+    ) where
+        T: ManySwap,
+    {
+        let IndexedBuildResult { node, rest } = a.build_indexed_and_next(perm, scratch);
+        self.add(node);
+        if let Some([(left, left_perm), (right, right_perm)]) = rest {
+            self.recurse_indexed(left, left_perm, scratch);
+            self.recurse_indexed(right, right_perm, scratch);
+        }
+    }
+
+    /// Recurse sequentially reusing the dividers of a previous tree
+    pub fn recurse_refit<S: Sorter<T>>(&mut self, a: TreeRefitVisitor<'a, '_, T>, sorter: &mut S)
+    where
+        T: ManySwap,
+    {
+        let NodeRefitResult { node, rest } = a.refit_and_next();
+        self.add(node.finish(sorter));
+        if let Some([left, right]) = rest {
+            self.recurse_refit(left, sorter);
+            self.recurse_refit(right, sorter);
+        }
+    }
 }
 
 #[derive(Copy, Clone, Default)]
@@ -340,15 +657,639 @@ impl<T: Aabb> Sorter<T> for DefaultSorter {
     }
 }
 
-fn partition_left<T>(arr: &mut [T], mut func: impl FnMut(&T) -> bool) -> (&mut [T], &mut [T]) {
+//For nodes whose elements were sorted before they were finished.
+struct SortedAlready;
+
+impl<T> Sorter<T> for SortedAlready {
+    fn sort(&self, _axis: impl Axis, _bots: &mut [T]) {}
+}
+
+//Sort the bots the same way DefaultSorter does, making every swap to perm as well.
+fn sort_indexed<T: Aabb>(
+    axis: impl Axis,
+    bots: &mut [T],
+    perm: &mut [usize],
+    scratch: &mut Vec<usize>,
+) {
+    scratch.clear();
+    scratch.extend(0..bots.len());
+    scratch.sort_unstable_by(|&a, &b| crate::queries::cmp_aabb(axis, &bots[a], &bots[b]));
+    permute(scratch, |a, b| {
+        bots.swap(a, b);
+        perm.swap(a, b);
+    });
+}
+
+///
+/// An integer that can be mapped to an unsigned key
+/// that sorts in the same order.
+///
+pub trait RadixKey: Num {
+    ///The number of bytes of the key that need to be sorted.
+    const NUM_BYTES: usize;
+
+    fn radix_key(self) -> u64;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const NUM_BYTES: usize = core::mem::size_of::<$t>();
+
+                #[inline(always)]
+                fn radix_key(self) -> u64 {
+                    self as u64
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const NUM_BYTES: usize = core::mem::size_of::<$t>();
+
+                #[inline(always)]
+                fn radix_key(self) -> u64 {
+                    //Flip the sign bit so that negative numbers come first.
+                    ((self as $u) ^ (1 << (<$u>::BITS - 1))) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+///
+/// Sorts integer aabbs with a least significant digit radix sort
+/// instead of a comparison sort.
+///
+/// Below [`RadixSorter::COMPARISON_THRESHOLD`] elements, there is not enough work
+/// to make up for the counting passes, so [`DefaultSorter`] is used instead.
+///
+#[derive(Copy, Clone, Default)]
+pub struct RadixSorter;
+
+impl RadixSorter {
+    pub const COMPARISON_THRESHOLD: usize = 64;
+}
+
+impl<T: Aabb> Sorter<T> for RadixSorter
+where
+    T::Num: RadixKey,
+{
+    fn sort(&self, axis: impl Axis, bots: &mut [T]) {
+        if bots.len() < Self::COMPARISON_THRESHOLD || bots.len() > u32::MAX as usize {
+            return DefaultSorter.sort(axis, bots);
+        }
+
+        let mut keys: Vec<(u64, u32)> = bots
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.range(axis).start.radix_key(), i as u32))
+            .collect();
+
+        radix_sort(&mut keys, T::Num::NUM_BYTES);
+
+        apply_permutation(bots, keys.into_iter().map(|(_, i)| i as usize).collect());
+    }
+}
+
+fn radix_sort(keys: &mut Vec<(u64, u32)>, num_bytes: usize) {
+    let mut buffer = vec![(0, 0); keys.len()];
+
+    for byte in 0..num_bytes {
+        let shift = byte * 8;
+        let digit = |a: &(u64, u32)| ((a.0 >> shift) & 0xff) as usize;
+
+        let mut counts = [0usize; 256];
+        for a in keys.iter() {
+            counts[digit(a)] += 1;
+        }
+
+        //Every key has the same digit, so this pass would not move anything.
+        if counts.contains(&keys.len()) {
+            continue;
+        }
+
+        let mut offsets = [0usize; 256];
+        for i in 1..256 {
+            offsets[i] = offsets[i - 1] + counts[i - 1];
+        }
+
+        for a in keys.iter() {
+            let d = digit(a);
+            buffer[offsets[d]] = *a;
+            offsets[d] += 1;
+        }
+
+        core::mem::swap(keys, &mut buffer);
+    }
+}
+
+//Move the element at perm[i] to position i, using only swaps.
+fn apply_permutation<T>(arr: &mut [T], mut perm: Vec<usize>) {
+    permute(&mut perm, |a, b| arr.swap(a, b));
+}
+
+//Make the swaps that move the element at order[i] to position i.
+//The order is left as the identity.
+fn permute(order: &mut [usize], mut swap: impl FnMut(usize, usize)) {
+    for i in 0..order.len() {
+        let mut current = i;
+        while order[current] != i {
+            let next = order[current];
+            swap(current, next);
+            order[current] = current;
+            current = next;
+        }
+        order[current] = current;
+    }
+}
+
+///
+/// Sorts the elements of each node taking advantage of them being nearly sorted
+/// already, as is the case when the elements only moved a little since the last
+/// frame and the tree is rebuilt with [`Tree::refit_coherent()`].
+///
+/// Each slice is fixed with an insertion sort, which only does as many
+/// swaps as there are pairs of elements out of order. Once that is more
+/// than the number of elements, such as when a large part of the slice
+/// is rotated, a stable sort that merges the already sorted runs is used.
+///
+#[derive(Copy, Clone, Default)]
+pub struct CoherentSorter;
+
+impl<T: Aabb> Sorter<T> for CoherentSorter {
+    fn sort(&self, axis: impl Axis, bots: &mut [T]) {
+        let cmp = |a: &T, b: &T| {
+            a.range(axis)
+                .start
+                .partial_cmp(&b.range(axis).start)
+                .unwrap_or(core::cmp::Ordering::Equal)
+        };
+
+        let mut num_swaps_left = bots.len();
+        for i in 1..bots.len() {
+            let mut j = i;
+            while j > 0 && cmp(&bots[j - 1], &bots[j]) == core::cmp::Ordering::Greater {
+                if num_swaps_left == 0 {
+                    bots.sort_by(cmp);
+                    return;
+                }
+                num_swaps_left -= 1;
+                bots.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+    }
+}
+
+///
+/// Decides where to place the divider of each node when building a tree.
+///
+pub trait DividerStrategy<T: Aabb> {
+    ///Return the divider of the non empty slice of bots along the specified axis.
+    ///The bots may be reordered.
+    fn divider(&self, axis: impl Axis, bots: &mut [T]) -> T::Num;
+
+    ///
+    /// Split the non empty slice of bots into the bots of the node, and the bots
+    /// to the left and right of the divider, in that order.
+    /// By default the bots are partitioned around [`DividerStrategy::divider()`].
+    ///
+    fn construct<'a>(
+        &self,
+        axis: impl Axis,
+        bots: &'a mut [T],
+    ) -> (NodeFinisher<'a, T>, &'a mut [T], &'a mut [T]) {
+        let div = self.divider(axis, bots);
+        construct_non_leaf_with_div(axis, div, bots)
+    }
+}
+
+///
+/// Divide at the median of the starts of the aabbs.
+/// This is what [`Tree::new()`] does.
+///
+#[derive(Copy, Clone, Default)]
+pub struct MedianDivider;
+
+impl<T: Aabb> DividerStrategy<T> for MedianDivider {
+    fn divider(&self, axis: impl Axis, bots: &mut [T]) -> T::Num {
+        let med_index = bots.len() / 2;
+        let (_, med, _) = bots
+            .select_nth_unstable_by(med_index, move |a, b| crate::queries::cmp_aabb(axis, a, b));
+        med.range(axis).start
+    }
+
+    fn construct<'a>(
+        &self,
+        axis: impl Axis,
+        bots: &'a mut [T],
+    ) -> (NodeFinisher<'a, T>, &'a mut [T], &'a mut [T]) {
+        construct_non_leaf(axis, bots)
+    }
+}
+
+///
+/// Surface area heuristic. Divide where the extent of each side of the divider
+/// weighted by the number of aabbs on that side is the smallest. Aabbs that
+/// touch the divider are weighted by the extent of the whole node.
+///
+/// Every start of an aabb is considered, so this is slower to build than
+/// [`MedianDivider`], but may result in fewer aabbs being checked against
+/// each other when the aabbs are not evenly distributed.
+///
+#[derive(Copy, Clone, Default)]
+pub struct SahDivider;
+
+impl<T: Aabb> DividerStrategy<T> for SahDivider
+where
+    T::Num: num_traits::ToPrimitive,
+{
+    fn divider(&self, axis: impl Axis, bots: &mut [T]) -> T::Num {
+        let mut starts: Vec<_> = bots.iter().map(|a| a.range(axis).start).collect();
+        let mut ends: Vec<_> = bots.iter().map(|a| a.range(axis).end).collect();
+        starts.sort_unstable_by(cmp_num);
+        ends.sort_unstable_by(cmp_num);
+
+        let n = bots.len();
+        let lo = to_f64(starts[0]);
+        let hi = to_f64(ends[n - 1]);
+
+        let mut best: Option<(f64, T::Num)> = None;
+        let mut num_left = 0;
+        for (i, &div) in starts.iter().enumerate() {
+            //Only consider the last of equal starts so that
+            //everything after it starts after the divider.
+            if starts.get(i + 1) == Some(&div) {
+                continue;
+            }
+
+            while num_left < n && ends[num_left] < div {
+                num_left += 1;
+            }
+            let num_right = n - (i + 1);
+            let num_mid = n - num_left - num_right;
+
+            let d = to_f64(div);
+            let cost = num_left as f64 * (d - lo)
+                + num_right as f64 * (hi - d)
+                + num_mid as f64 * (hi - lo);
+
+            match best {
+                Some((c, _)) if c <= cost => {}
+                _ => best = Some((cost, div)),
+            }
+        }
+        best.unwrap().1
+    }
+}
+
+///
+/// Bin the aabbs into a histogram and divide at the bin boundary
+/// that the fewest aabbs straddle, so long as both sides of the divider
+/// get at least a quarter of the aabbs. If there is no such boundary,
+/// divide at the median.
+///
+#[derive(Copy, Clone)]
+pub struct HistogramDivider {
+    pub num_bins: usize,
+}
+
+impl Default for HistogramDivider {
+    fn default() -> Self {
+        HistogramDivider { num_bins: 32 }
+    }
+}
+
+impl<T: Aabb> DividerStrategy<T> for HistogramDivider
+where
+    T::Num: num_traits::NumCast,
+{
+    fn divider(&self, axis: impl Axis, bots: &mut [T]) -> T::Num {
+        let n = bots.len();
+        let num_bins = self.num_bins.max(2);
+
+        let lo = bots
+            .iter()
+            .map(|a| to_f64(a.range(axis).start))
+            .fold(f64::INFINITY, f64::min);
+        let hi = bots
+            .iter()
+            .map(|a| to_f64(a.range(axis).end))
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let width = (hi - lo) / num_bins as f64;
+        if !width.is_finite() || width <= 0.0 {
+            return MedianDivider.divider(axis, bots);
+        }
+
+        let bin = |a: T::Num| (((to_f64(a) - lo) / width) as usize).min(num_bins - 1);
+
+        let mut start_bins = vec![0; num_bins];
+        let mut end_bins = vec![0; num_bins];
+        for a in bots.iter() {
+            let r = a.range(axis);
+            start_bins[bin(r.start)] += 1;
+            end_bins[bin(r.end)] += 1;
+        }
+
+        //Aabbs that end in a bin before the boundary are to the left of it.
+        //Aabbs that start in a bin after the boundary are to the right of it.
+        let mut best: Option<(usize, usize)> = None;
+        let mut num_left = 0;
+        let mut num_right = n;
+        for k in 1..num_bins {
+            num_left += end_bins[k - 1];
+            num_right -= start_bins[k - 1];
+            let num_mid = n - num_left - num_right;
+
+            if num_left.min(num_right) * 4 < n {
+                continue;
+            }
+
+            match best {
+                Some((m, _)) if m <= num_mid => {}
+                _ => best = Some((num_mid, k)),
+            }
+        }
+
+        match best.and_then(|(_, k)| num_traits::NumCast::from(lo + k as f64 * width)) {
+            Some(div) => div,
+            None => MedianDivider.divider(axis, bots),
+        }
+    }
+}
+
+fn cmp_num<N: PartialOrd>(a: &N, b: &N) -> core::cmp::Ordering {
+    if a > b {
+        core::cmp::Ordering::Greater
+    } else {
+        core::cmp::Ordering::Less
+    }
+}
+
+fn to_f64<N: num_traits::ToPrimitive>(a: N) -> f64 {
+    a.to_f64().unwrap()
+}
+
+fn axis_of_largest_extent<T: Aabb>(bots: &[T]) -> AxisDyn
+where
+    T::Num: num_traits::ToPrimitive,
+{
+    let extent = |r: fn(&Rect<T::Num>) -> &Range<T::Num>| {
+        let (lo, hi) = bots
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), a| {
+                let r = r(a.get());
+                (lo.min(to_f64(r.start)), hi.max(to_f64(r.end)))
+            });
+        hi - lo
+    };
+
+    if extent(|r| &r.y) > extent(|r| &r.x) {
+        AxisDyn::Y
+    } else {
+        AxisDyn::X
+    }
+}
+
+fn construct_non_leaf_by<'a, T: Aabb, D: DividerStrategy<T>>(
+    div_axis: impl Axis,
+    bots: &'a mut [T],
+    divider: &D,
+) -> (NodeFinisher<'a, T>, &'a mut [T], &'a mut [T]) {
+    if bots.is_empty() {
+        return construct_non_leaf(div_axis, bots);
+    }
+    divider.construct(div_axis, bots)
+}
+
+fn construct_non_leaf<T: Aabb>(
+    div_axis: impl Axis,
+    bots: &mut [T],
+) -> (NodeFinisher<T>, &mut [T], &mut [T]) {
+    if bots.is_empty() {
+        return (
+            NodeFinisher {
+                middle_left_len: None,
+                mid: bots,
+                div: None,
+                axis: div_axis.to_dyn(),
+                min_elem: 0,
+                num_elem: 0,
+            },
+            &mut [],
+            &mut [],
+        );
+    }
+
+    let med_index = bots.len() / 2;
+
+    let (ll, med, rr) = bots.select_nth_unstable_by(med_index, move |a, b| {
+        crate::queries::cmp_aabb(div_axis, a, b)
+    });
+
+    let med_val = med.range(div_axis).start;
+
+    let (ml, ll) = partition_left(ll, |a| a.range(div_axis).end >= med_val);
+    let (mr, rr) = partition_left(rr, |a| a.range(div_axis).start <= med_val);
+
+    let ml_len = ml.len();
+    let ll_len = ll.len();
+    let rr_len = rr.len();
+    let mr_len = mr.len();
+
+    //At this point we have:
+    // [ml,ll,mr,rr]
+    //move stuff around so we have:
+    // [ml,mr,ll,rr]
+    {
+        let (_, rest) = bots.split_at_mut(ml_len);
+        let (arr, _) = rest.split_at_mut(ll_len + 1 + mr_len);
+        swap_slice_different_sizes(arr, ll_len)
+    }
+
+    let left_len = ll_len;
+    let right_len = rr_len;
+    let mid_len = ml_len + 1 + mr_len;
+    let middle_left_len = Some(ml_len + 1);
+
+    let (mid, rest) = bots.split_at_mut(mid_len);
+    let (left, right) = rest.split_at_mut(left_len);
+
+    (
+        NodeFinisher {
+            middle_left_len,
+            mid,
+            div: Some(med_val),
+            axis: div_axis.to_dyn(),
+            min_elem: left_len.min(right_len),
+            num_elem: left_len + right_len,
+        },
+        left,
+        right,
+    )
+}
+
+//Partition the bots around a divider that was already decided on.
+//Bots that are already on the correct side of the divider are not moved.
+fn construct_non_leaf_with_div<T: Aabb>(
+    div_axis: impl Axis,
+    div: T::Num,
+    bots: &mut [T],
+) -> (NodeFinisher<T>, &mut [T], &mut [T]) {
+    if bots.is_empty() {
+        return construct_non_leaf(div_axis, bots);
+    }
+
+    let (mid, rest) = partition_left(bots, |a| a.range(div_axis).contains(div));
+    let (left, right) = partition_left(rest, |a| a.range(div_axis).end < div);
+
+    finish_partition(div_axis, div, mid, left, right)
+}
+
+//Like construct_non_leaf() except the divider is the median found through
+//the scratch indices, and every swap of the bots is made to perm as well.
+fn construct_non_leaf_indexed<'a, T: Aabb>(
+    div_axis: impl Axis,
+    bots: &'a mut [T],
+    perm: &mut [usize],
+    scratch: &mut Vec<usize>,
+) -> (NodeFinisher<'a, T>, &'a mut [T], &'a mut [T]) {
+    if bots.is_empty() {
+        return construct_non_leaf(div_axis, bots);
+    }
+
+    scratch.clear();
+    scratch.extend(0..bots.len());
+    let (_, med, _) = scratch.select_nth_unstable_by(bots.len() / 2, |&a, &b| {
+        crate::queries::cmp_aabb(div_axis, &bots[a], &bots[b])
+    });
+    let div = bots[*med].range(div_axis).start;
+
+    let num_mid = partition_left_with(
+        bots,
+        |a| a.range(div_axis).contains(div),
+        |a, b| perm.swap(a, b),
+    );
+    let (mid, rest) = bots.split_at_mut(num_mid);
+    let perm = &mut perm[num_mid..];
+    let num_left = partition_left_with(
+        rest,
+        |a| a.range(div_axis).end < div,
+        |a, b| perm.swap(a, b),
+    );
+    let (left, right) = rest.split_at_mut(num_left);
+
+    finish_partition(div_axis, div, mid, left, right)
+}
+
+fn finish_partition<'a, T: Aabb>(
+    div_axis: impl Axis,
+    div: T::Num,
+    mid: &'a mut [T],
+    left: &'a mut [T],
+    right: &'a mut [T],
+) -> (NodeFinisher<'a, T>, &'a mut [T], &'a mut [T]) {
+    let left_len = left.len();
+    let right_len = right.len();
+
+    (
+        NodeFinisher {
+            middle_left_len: None,
+            mid,
+            div: Some(div),
+            axis: div_axis.to_dyn(),
+            min_elem: left_len.min(right_len),
+            num_elem: left_len + right_len,
+        },
+        left,
+        right,
+    )
+}
+
+//Like construct_non_leaf_with_div() except bots that end up
+//in the same place keep their relative order.
+fn construct_non_leaf_with_div_stable<T: Aabb>(
+    div_axis: impl Axis,
+    div: T::Num,
+    bots: &mut [T],
+) -> (NodeFinisher<T>, &mut [T], &mut [T]) {
+    if bots.is_empty() {
+        return construct_non_leaf(div_axis, bots);
+    }
+
+    let num_mid = stable_partition(bots, &mut |a| a.range(div_axis).contains(div));
+    let (mid, rest) = bots.split_at_mut(num_mid);
+    let num_left = stable_partition(rest, &mut |a| a.range(div_axis).end < div);
+    let (left, right) = rest.split_at_mut(num_left);
+
+    let left_len = left.len();
+    let right_len = right.len();
+
+    (
+        NodeFinisher {
+            middle_left_len: None,
+            mid,
+            div: Some(div),
+            axis: div_axis.to_dyn(),
+            min_elem: left_len.min(right_len),
+            num_elem: left_len + right_len,
+        },
+        left,
+        right,
+    )
+}
+
+//Move the bots for which the predicate is true to the front without changing the
+//relative order of either group, and return how many there are. Halves that are
+//already partitioned are only rotated, so little is moved if few bots changed groups.
+fn stable_partition<T>(arr: &mut [T], pred: &mut impl FnMut(&T) -> bool) -> usize {
+    match arr.len() {
+        0 => 0,
+        1 => usize::from(pred(&arr[0])),
+        len => {
+            let half = len / 2;
+            let a = stable_partition(&mut arr[..half], pred);
+            let b = stable_partition(&mut arr[half..], pred);
+            arr[a..half + b].rotate_left(half - a);
+            a + b
+        }
+    }
+}
+
+//Bots are partitioned as if their ranges were closed in every IntersectionMode.
+//For half open bots that only puts a few more in the middle than needed,
+//since queries check every pair with the right mode regardless.
+fn partition_left<T>(arr: &mut [T], func: impl FnMut(&T) -> bool) -> (&mut [T], &mut [T]) {
+    let m = partition_left_with(arr, func, |_, _| {});
+    arr.split_at_mut(m)
+}
+
+//Like partition_left() except every swap made is passed to a function
+//and the number of bots for which the predicate is true is returned.
+fn partition_left_with<T>(
+    arr: &mut [T],
+    mut func: impl FnMut(&T) -> bool,
+    mut swap: impl FnMut(usize, usize),
+) -> usize {
     let mut m = 0;
     for a in 0..arr.len() {
         if func(&arr[a]) {
             arr.swap(a, m);
+            swap(a, m);
             m += 1;
         }
     }
-    arr.split_at_mut(m)
+    m
 }
 
 // swap a (l)(s) to (s)(l)
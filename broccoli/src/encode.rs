@@ -0,0 +1,285 @@
+//!
+//! A compact versioned binary encoding of [`TreeData`].
+//!
+//! This lets a tree be built offline, written to a file,
+//! and later reattached to the same elements with [`Tree::from_tree_data()`].
+//!
+//! ```rust
+//! use broccoli::{rect, Tree, TreeData};
+//!
+//! let mut bots = vec![
+//!     (rect(0, 10, 0, 10), ()),
+//!     (rect(5, 15, 5, 15), ()),
+//! ];
+//!
+//! let bytes = Tree::new(&mut bots).get_tree_data().to_bytes();
+//!
+//! let data = TreeData::<i32>::from_bytes(&bytes).unwrap();
+//! let mut tree = Tree::from_tree_data(&mut bots, &data);
+//!
+//! let mut num = 0;
+//! tree.find_colliding_pairs(|_, _| num += 1);
+//! assert_eq!(num, 1);
+//! ```
+//!
+//! The layout is:
+//!
+//! * The bytes `b"BRCL"`
+//! * A version byte, currently [`VERSION`]
+//! * A byte with the size of the number type in bytes
+//! * The number of nodes as a varint
+//! * For each node in dfs in order:
+//!     * The number of elements in the node as a varint
+//!     * `min_elem` as a varint
+//!     * A flags byte. Bit 0 is set if the axis is `Y`. Bit 1 is set if there is a divider.
+//!     * `cont.start` and `cont.end`
+//!     * The divider, if there is one
+//!
+//! Varints are LEB128 encoded. Numbers are little endian.
+//!
+
+use super::*;
+
+const MAGIC: &[u8; 4] = b"BRCL";
+
+///
+/// The version of the encoding produced by [`TreeData::to_bytes()`].
+///
+pub const VERSION: u8 = 1;
+
+const FLAG_YAXIS: u8 = 0b01;
+const FLAG_DIV: u8 = 0b10;
+
+///
+/// A number type that can be written to and read from bytes.
+///
+pub trait EncodeNum: Num {
+    ///The number of bytes an encoded number takes up.
+    const SIZE: usize;
+
+    fn encode(self, buffer: &mut Vec<u8>);
+
+    ///Decode from exactly [`EncodeNum::SIZE`] bytes.
+    fn decode(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_encode_num {
+    ($($t:ty),*) => {
+        $(
+            impl EncodeNum for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+
+                fn encode(self, buffer: &mut Vec<u8>) {
+                    buffer.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_encode_num!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+///
+/// Why bytes could not be decoded into a [`TreeData`].
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes do not start with the expected magic bytes.
+    BadMagic,
+    /// The bytes were encoded with a version of the encoding that is not supported.
+    UnsupportedVersion(u8),
+    /// The bytes were encoded with a number type of a different size.
+    NumSizeMismatch { expected: usize, found: usize },
+    /// The bytes ended before the tree data was decoded.
+    UnexpectedEnd,
+    /// A flags byte has unknown bits set.
+    InvalidFlags(u8),
+    /// The number of nodes does not make a complete binary tree.
+    InvalidNodeCount(usize),
+    /// There are bytes left over after the tree data was decoded.
+    TrailingBytes,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not broccoli tree data"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            DecodeError::NumSizeMismatch { expected, found } => write!(
+                f,
+                "expected numbers of {} bytes but found {} bytes",
+                expected, found
+            ),
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of bytes"),
+            DecodeError::InvalidFlags(b) => write!(f, "invalid node flags {:#04x}", b),
+            DecodeError::InvalidNodeCount(n) => {
+                write!(f, "{} nodes do not make a complete binary tree", n)
+            }
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after tree data"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn write_varint(mut a: usize, buffer: &mut Vec<u8>) {
+    loop {
+        let byte = (a & 0x7f) as u8;
+        a >>= 7;
+        if a == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, num: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < num {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (a, rest) = self.bytes.split_at(num);
+        self.bytes = rest;
+        Ok(a)
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<usize, DecodeError> {
+        let mut a: usize = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            let bits = ((byte & 0x7f) as usize)
+                .checked_shl(shift)
+                .ok_or(DecodeError::UnexpectedEnd)?;
+            a |= bits;
+            if byte & 0x80 == 0 {
+                return Ok(a);
+            }
+            shift += 7;
+        }
+    }
+
+    fn num<N: EncodeNum>(&mut self) -> Result<N, DecodeError> {
+        Ok(N::decode(self.take(N::SIZE)?))
+    }
+}
+
+impl<N: EncodeNum> TreeData<N> {
+    ///
+    /// Encode the tree data. See the [`encode`](crate::encode) module for the layout.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(MAGIC);
+        buffer.push(VERSION);
+        buffer.push(N::SIZE as u8);
+        write_varint(self.nodes.len(), &mut buffer);
+
+        for node in self.nodes.iter() {
+            write_varint(node.range, &mut buffer);
+            write_varint(node.min_elem, &mut buffer);
+
+            let mut flags = 0;
+            if let AxisDyn::Y = node.axis {
+                flags |= FLAG_YAXIS;
+            }
+            if node.div.is_some() {
+                flags |= FLAG_DIV;
+            }
+            buffer.push(flags);
+
+            node.cont.start.encode(&mut buffer);
+            node.cont.end.encode(&mut buffer);
+            if let Some(div) = node.div {
+                div.encode(&mut buffer);
+            }
+        }
+        buffer
+    }
+
+    ///
+    /// Decode tree data produced by [`TreeData::to_bytes()`].
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader { bytes };
+
+        if r.take(MAGIC.len()).map_err(|_| DecodeError::BadMagic)? != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+
+        let version = r.byte()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let size = r.byte()? as usize;
+        if size != N::SIZE {
+            return Err(DecodeError::NumSizeMismatch {
+                expected: N::SIZE,
+                found: size,
+            });
+        }
+
+        let num_nodes = r.varint()?;
+        //Every node takes up at least this many bytes,
+        //so don't trust a node count that is bigger than that.
+        let min_node_size = 3 + 2 * N::SIZE;
+        if num_nodes > r.bytes.len() / min_node_size {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        let mut nodes = Vec::with_capacity(num_nodes);
+        for _ in 0..num_nodes {
+            let range = r.varint()?;
+            let min_elem = r.varint()?;
+
+            let flags = r.byte()?;
+            if flags & !(FLAG_YAXIS | FLAG_DIV) != 0 {
+                return Err(DecodeError::InvalidFlags(flags));
+            }
+
+            let start = r.num()?;
+            let end = r.num()?;
+            let div = if flags & FLAG_DIV != 0 {
+                Some(r.num()?)
+            } else {
+                None
+            };
+
+            nodes.push(NodeData {
+                range,
+                cont: axgeom::Range { start, end },
+                div,
+                axis: if flags & FLAG_YAXIS != 0 {
+                    AxisDyn::Y
+                } else {
+                    AxisDyn::X
+                },
+                min_elem,
+            });
+        }
+
+        if !r.bytes.is_empty() {
+            return Err(DecodeError::TrailingBytes);
+        }
+
+        if compt::dfs_order::CompleteTree::from_preorder(&nodes).is_err() {
+            return Err(DecodeError::InvalidNodeCount(nodes.len()));
+        }
+
+        Ok(TreeData { nodes })
+    }
+}
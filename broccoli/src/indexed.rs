@@ -0,0 +1,203 @@
+//!
+//! A tree that remembers where its elements came from, so that
+//! colliding pairs can be returned as indices into the slice it was built from.
+//!
+//! ```rust
+//! use broccoli::{indexed::IndexedTree, rect};
+//!
+//! let mut aabbs = [
+//!     rect(0, 10, 0, 10),
+//!     rect(15, 20, 15, 20),
+//!     rect(5, 15, 5, 15),
+//! ];
+//!
+//! let mut tree = IndexedTree::new(&mut aabbs);
+//!
+//! assert_eq!(tree.find_colliding_pair_indices(true), vec![(0, 2), (1, 2)]);
+//! ```
+//!
+
+use super::*;
+use core::marker::PhantomData;
+
+///
+/// Finds the position of an element within the slice of the tree it belongs to.
+/// Returned by [`Tree::positions()`]
+///
+pub struct TreePositions<T> {
+    base: usize,
+    len: usize,
+    _p: PhantomData<fn(&T)>,
+}
+
+impl<T> Clone for TreePositions<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TreePositions<T> {}
+
+impl<T> TreePositions<T> {
+    ///Returns None if the element does not belong to the tree this was returned by.
+    #[inline(always)]
+    #[must_use]
+    pub fn of(&self, a: &T) -> Option<usize> {
+        let size = core::mem::size_of::<T>();
+        let offset = (a as *const T as usize).checked_sub(self.base)?;
+        if offset % size != 0 {
+            return None;
+        }
+        let index = offset / size;
+        if index < self.len {
+            Some(index)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// Panics if the elements are zero sized.
+    ///
+    #[must_use]
+    pub fn positions(&self) -> TreePositions<T> {
+        assert!(
+            core::mem::size_of::<T>() != 0,
+            "zero sized elements do not have a position"
+        );
+
+        let base = self
+            .get_nodes()
+            .first()
+            .map(|n| n.range.as_ptr() as usize)
+            .unwrap_or(0);
+        let len = self.get_nodes().iter().map(|n| n.range.len()).sum();
+
+        TreePositions {
+            base,
+            len,
+            _p: PhantomData,
+        }
+    }
+}
+
+///
+/// A [`Tree`] along with the index each of its elements had in the slice
+/// it was built from.
+///
+pub struct IndexedTree<'a, T: Aabb> {
+    tree: Tree<'a, T>,
+    //For every element in tree order, its index before the tree was built.
+    perm: Vec<usize>,
+}
+
+impl<'a, T: Aabb> IndexedTree<'a, T> {
+    ///
+    /// Like [`Tree::new()`] except the elements are followed
+    /// as they are moved around to build the tree.
+    ///
+    pub fn new(bots: &'a mut [T]) -> Self
+    where
+        T: ManySwap,
+    {
+        let mut perm: Vec<_> = (0..bots.len()).collect();
+        let (mut e, v) = TreeEmbryo::new(bots);
+        e.recurse_indexed(v, &mut perm, &mut Vec::new());
+        IndexedTree {
+            tree: e.finish(),
+            perm,
+        }
+    }
+
+    ///
+    /// User responsibility to provide, for every element of the tree in
+    /// tree order, the index it had before the tree was built.
+    /// See [`build::TreeEmbryo::recurse_indexed()`]
+    ///
+    pub fn from_parts(tree: Tree<'a, T>, perm: Vec<usize>) -> Self {
+        IndexedTree { tree, perm }
+    }
+
+    #[must_use]
+    pub fn as_tree(&self) -> &Tree<'a, T> {
+        &self.tree
+    }
+
+    #[must_use]
+    pub fn as_tree_mut(&mut self) -> &mut Tree<'a, T> {
+        &mut self.tree
+    }
+
+    ///
+    /// The tree along with its permutation, for queries that need both at once.
+    ///
+    #[must_use]
+    pub fn parts_mut(&mut self) -> (&mut Tree<'a, T>, &[usize]) {
+        (&mut self.tree, &self.perm)
+    }
+
+    ///
+    /// For every element in tree order, the index it had in the slice
+    /// the tree was built from.
+    ///
+    #[must_use]
+    pub fn get_permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    ///
+    /// Find all colliding pairs as indices into the slice the tree was built from.
+    /// Every pair appears once, with the smaller index first.
+    /// If `sorted` is true, the pairs are sorted as well.
+    ///
+    /// Panics if the elements are zero sized.
+    ///
+    #[must_use]
+    pub fn find_colliding_pair_indices(&mut self, sorted: bool) -> Vec<(usize, usize)> {
+        let (tree, perm) = self.parts_mut();
+        let positions = tree.positions();
+        let index = |a: &T| perm[positions.of(a).unwrap()];
+
+        let mut pairs = Vec::new();
+        tree.find_colliding_pairs(|a, b| {
+            let (a, b) = (index(&a), index(&b));
+            pairs.push((a.min(b), a.max(b)));
+        });
+
+        if sorted {
+            pairs.sort_unstable();
+        }
+        pairs
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
+        ///
+        /// Panics if a disconnect is detected between tree and naive queries.
+        /// Panics if the elements are zero sized.
+        ///
+        pub fn assert_colliding_pair_indices(&mut self) {
+            use crate::assert::into_ptr_usize;
+
+            let base = self.inner.as_ptr() as usize;
+            let index = |a: AabbPin<&mut T>| (into_ptr_usize(a) - base) / core::mem::size_of::<T>();
+
+            let mut res_naive = vec![];
+            Naive::new(self.inner).find_colliding_pairs(|a, b| {
+                let (a, b) = (index(a), index(b));
+                res_naive.push((a.min(b), a.max(b)));
+            });
+            res_naive.sort_unstable();
+
+            let res_dino = IndexedTree::new(self.inner).find_colliding_pair_indices(true);
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+        }
+    }
+}
@@ -43,7 +43,13 @@ use node::*;
 mod tests;
 
 pub mod assert;
+pub mod encode;
+pub mod indexed;
+pub mod owned;
 pub mod queries;
+pub mod scene;
+pub mod stats;
+pub mod tree3d;
 
 use assert::Assert;
 use assert::Naive;
@@ -72,6 +78,7 @@ pub fn bbox_mut<N, T>(rect: axgeom::Rect<N>, inner: &mut T) -> BBoxMut<N, T> {
 /// See [`Tree::get_tree_data()`] and [`Tree::from_tree_data()`]
 ///
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TreeData<N: Num> {
     nodes: Vec<NodeData<N>>,
 }
@@ -189,6 +196,7 @@ impl<'a, T: Aabb + 'a> Tree<'a, T> {
                     range: AabbPin::from_mut(range),
                     cont: x.cont,
                     div: x.div,
+                    axis: x.axis,
                     min_elem: x.min_elem,
                     //num_elem: x.num_elem,
                 }
@@ -198,6 +206,80 @@ impl<'a, T: Aabb + 'a> Tree<'a, T> {
         Tree { nodes }
     }
 
+    ///
+    /// Like [`Tree::from_tree_data()`] except an error is returned instead of panicking
+    /// if the tree data does not have the number of nodes of a complete tree,
+    /// if it does not hold the same number of elements as were provided,
+    /// or if an aabb has a NaN bound or an inverted range.
+    ///
+    /// This does not check that the elements are in the same order as
+    /// when the tree data was made. See [`assert::assert_tree_invariants()`] for that.
+    ///
+    pub fn try_from_tree_data(
+        bots: &'a mut [T],
+        data: &TreeData<T::Num>,
+    ) -> Result<Self, TreeError> {
+        if compt::dfs_order::CompleteTree::from_preorder(&data.nodes).is_err() {
+            return Err(TreeError::MalformedTreeData {
+                num_nodes: data.nodes.len(),
+            });
+        }
+
+        let expected = data.nodes.iter().map(|x| x.range).sum();
+        if expected != bots.len() {
+            return Err(TreeError::LengthMismatch {
+                expected,
+                found: bots.len(),
+            });
+        }
+        validate_aabbs(bots)?;
+        Ok(Self::from_tree_data(bots, data))
+    }
+
+    ///
+    /// Create a new tree reusing the dividers of a previously built tree.
+    ///
+    /// No medians are searched for. Instead the elements are partitioned
+    /// around the dividers stored in the tree data, and only the elements
+    /// that crossed a divider are moved. If the elements are in the same order
+    /// as when [`Tree::get_tree_data()`] was called and only moved a little,
+    /// this is much cheaper than building a new tree.
+    ///
+    /// Unlike [`Tree::from_tree_data()`], the resulting tree is always valid
+    /// regardless of the order of the elements. However, the dividers are not
+    /// rebalanced, so as the elements drift the tree may become lopsided.
+    /// Consider building a new tree every once in a while.
+    ///
+    /// Panics if the tree data does not have the number of nodes of a complete tree.
+    ///
+    pub fn refit(bots: &'a mut [T], data: &TreeData<T::Num>) -> Self
+    where
+        T: ManySwap,
+    {
+        let (mut e, v) = TreeEmbryo::with_tree_data(bots, data);
+        e.recurse_refit(v, &mut DefaultSorter);
+        e.finish()
+    }
+
+    ///
+    /// Like [`Tree::refit()`] except the elements keep the order the previous tree
+    /// left them in wherever they can, and are sorted with a [`build::CoherentSorter`].
+    /// If the elements only moved a little, the elements of each node are nearly
+    /// sorted already, so there is less sorting to do than with [`Tree::refit()`].
+    ///
+    /// This only helps if the same slice that the previous tree was built from
+    /// is passed in, without being reordered in between.
+    /// See [`TreeEmbryo::with_previous_order()`] otherwise.
+    ///
+    pub fn refit_coherent(bots: &'a mut [T], data: &TreeData<T::Num>) -> Self
+    where
+        T: ManySwap,
+    {
+        let (mut e, v) = TreeEmbryo::with_tree_data(bots, data);
+        e.recurse_refit(v.keep_order(), &mut CoherentSorter);
+        e.finish()
+    }
+
     ///
     /// Create a new tree with the default tree height heuristic
     ///
@@ -210,6 +292,63 @@ impl<'a, T: Aabb + 'a> Tree<'a, T> {
         e.finish()
     }
 
+    ///
+    /// Like [`Tree::new()`] except an error is returned if an aabb has a NaN bound
+    /// or an inverted range, either of which would make an invalid tree.
+    ///
+    pub fn try_new(bots: &'a mut [T]) -> Result<Self, TreeError>
+    where
+        T: ManySwap,
+    {
+        let (mut e, v) = TreeEmbryo::try_new(bots)?;
+        e.recurse(v, &mut DefaultSorter);
+        Ok(e.finish())
+    }
+
+    ///
+    /// Create a new tree with the default tree height heuristic,
+    /// using the specified strategy to decide where to place the dividers.
+    /// See [`build::DividerStrategy`]
+    ///
+    pub fn new_with_divider<D: DividerStrategy<T>>(bots: &'a mut [T], divider: D) -> Self
+    where
+        T: ManySwap,
+    {
+        let (mut e, v) = TreeEmbryo::new(bots);
+        e.recurse_with(v, &mut DefaultSorter, &divider);
+        e.finish()
+    }
+
+    ///
+    /// Create a new tree with the default tree height heuristic,
+    /// using the specified sorter to sort the elements of each node.
+    /// For integer aabbs, consider [`build::RadixSorter`].
+    ///
+    pub fn new_with_sorter<S: Sorter<T>>(bots: &'a mut [T], mut sorter: S) -> Self
+    where
+        T: ManySwap,
+    {
+        let (mut e, v) = TreeEmbryo::new(bots);
+        e.recurse(v, &mut sorter);
+        e.finish()
+    }
+
+    ///
+    /// Create a new tree with the default tree height heuristic,
+    /// splitting every node along the axis in which its elements
+    /// are the most spread out instead of alternating between axes.
+    /// This suits long and thin worlds better.
+    ///
+    pub fn new_by_extent(bots: &'a mut [T]) -> Self
+    where
+        T: ManySwap,
+        T::Num: num_traits::ToPrimitive,
+    {
+        let (mut e, v) = TreeEmbryo::new(bots);
+        e.recurse(v.split_by_extent(), &mut DefaultSorter);
+        e.finish()
+    }
+
     #[inline(always)]
     pub fn vistr_mut(&mut self) -> VistrMutPin<Node<'a, T, T::Num>> {
         let tree = compt::dfs_order::CompleteTreeMut::from_preorder_mut(&mut self.nodes).unwrap();
@@ -9,6 +9,9 @@ use super::*;
 /// change anything.
 pub type Vistr<'a, N> = compt::dfs_order::Vistr<'a, N, compt::dfs_order::PreOrder>;
 
+/// The children of a node when visiting a tree with depth information.
+pub(crate) type LevelRest<V> = Option<[compt::LevelIter<V>; 2]>;
+
 mod vistr_mut {
     use super::*;
     use compt::Visitor;
@@ -92,6 +95,16 @@ pub struct Node<'a, T, N> {
     ///   value is none
     pub div: Option<N>,
 
+    ///
+    /// The axis that the divider partitions space along.
+    /// The elements of this node are sorted along the other axis.
+    ///
+    /// Trees built with [`Tree::new()`] alternate between the x and y axis
+    /// every level, but trees built with [`Tree::new_by_extent()`] do not,
+    /// so queries should read this instead of inferring it from the depth.
+    ///
+    pub axis: AxisDyn,
+
     ///
     /// The minimum number of elements in a child node.
     /// If the left child has 500 bots, and the right child has 20, then
@@ -114,6 +127,7 @@ impl<'a, T, N: Num> Node<'a, T, N> {
             range: self.range.len(),
             cont: self.cont,
             div: self.div,
+            axis: self.axis,
             min_elem: self.min_elem,
             //num_elem: self.num_elem,
         }
@@ -124,10 +138,33 @@ impl<'a, T, N: Num> Node<'a, T, N> {
 /// Like [`Node`] except only has the number of elem instead of a slice..
 ///
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeData<N: Num> {
     pub range: usize,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::RangeDef"))]
     pub cont: axgeom::Range<N>,
     pub div: Option<N>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_remote::AxisDynDef"))]
+    pub axis: AxisDyn,
     pub min_elem: usize,
     //pub num_elem: usize,
 }
+
+#[cfg(feature = "serde")]
+mod serde_remote {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "axgeom::Range")]
+    pub struct RangeDef<N> {
+        pub start: N,
+        pub end: N,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "axgeom::AxisDyn")]
+    pub enum AxisDynDef {
+        X,
+        Y,
+    }
+}
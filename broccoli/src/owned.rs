@@ -0,0 +1,350 @@
+//!
+//! A tree that owns its elements so that it can be stored across frames.
+//!
+//! ```rust
+//! use broccoli::{owned::OwnedTree, rect};
+//!
+//! let mut tree = OwnedTree::new(vec![
+//!     (rect(00, 10, 00, 10), 0usize),
+//!     (rect(15, 20, 15, 20), 1),
+//! ]);
+//!
+//! let c = tree.insert((rect(05, 15, 05, 15), 2));
+//!
+//! let mut num = 0;
+//! tree.as_tree().find_colliding_pairs(|_, _| num += 1);
+//! assert_eq!(num, 2);
+//!
+//! tree.remove(c);
+//!
+//! let mut num = 0;
+//! tree.as_tree().find_colliding_pairs(|_, _| num += 1);
+//! assert_eq!(num, 0);
+//! ```
+//!
+
+use super::*;
+
+///
+/// If one child of a node has this many times more elements than the other,
+/// the tree is rebuilt from scratch instead of reusing its dividers.
+///
+const REBALANCE_RATIO: usize = 4;
+
+///
+/// A key to an element in an [`OwnedTree`].
+///
+/// A handle is never reused. Once its element is removed, it will
+/// not refer to an element inserted later.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle {
+    index: usize,
+    generation: usize,
+}
+
+///
+/// An element of an [`OwnedTree`] along with its [`Handle`].
+///
+#[derive(Debug)]
+pub struct OwnedElem<T> {
+    inner: T,
+    handle: Handle,
+}
+
+impl<T> OwnedElem<T> {
+    #[inline(always)]
+    pub fn handle(&self) -> Handle {
+        self.handle
+    }
+
+    #[inline(always)]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ManySwap> ManySwap for OwnedElem<T> {}
+
+impl<T: Aabb> Aabb for OwnedElem<T> {
+    type Num = T::Num;
+    const INTERSECTION_MODE: IntersectionMode = T::INTERSECTION_MODE;
+    #[inline(always)]
+    fn get(&self) -> &Rect<Self::Num> {
+        self.inner.get()
+    }
+}
+
+impl<T: HasInner> HasInner for OwnedElem<T> {
+    type Inner = T::Inner;
+    #[inline(always)]
+    fn destruct_mut(&mut self) -> (&Rect<Self::Num>, &mut Self::Inner) {
+        self.inner.destruct_mut()
+    }
+}
+
+#[derive(Debug)]
+struct Slot {
+    generation: usize,
+    index: Option<usize>,
+}
+
+///
+/// A broccoli tree that owns its elements.
+///
+/// Elements can be inserted and removed at any time. The tree is
+/// only updated when it is next queried through [`OwnedTree::as_tree()`].
+/// It is then refitted using the dividers from last time, see [`Tree::refit()`],
+/// or rebuilt from scratch if the elements are no longer evenly distributed
+/// among the nodes.
+///
+pub struct OwnedTree<T: Aabb> {
+    elems: Vec<OwnedElem<T>>,
+    data: TreeData<T::Num>,
+    slots: Vec<Slot>,
+    free_slots: Vec<usize>,
+    dirty: bool,
+}
+
+impl<T: Aabb + ManySwap> OwnedTree<T> {
+    pub fn new(elems: Vec<T>) -> Self {
+        let slots = (0..elems.len())
+            .map(|index| Slot {
+                generation: 0,
+                index: Some(index),
+            })
+            .collect();
+
+        let mut elems: Vec<_> = elems
+            .into_iter()
+            .enumerate()
+            .map(|(index, inner)| OwnedElem {
+                inner,
+                handle: Handle {
+                    index,
+                    generation: 0,
+                },
+            })
+            .collect();
+
+        let data = Tree::new(&mut elems).get_tree_data();
+
+        let mut k = OwnedTree {
+            elems,
+            data,
+            slots,
+            free_slots: Vec::new(),
+            dirty: false,
+        };
+        k.update_slots();
+        k
+    }
+
+    ///
+    /// Add an element. The tree is updated the next time it is queried.
+    ///
+    pub fn insert(&mut self, elem: T) -> Handle {
+        let index = self.elems.len();
+        let handle = match self.free_slots.pop() {
+            Some(slot) => {
+                let s = &mut self.slots[slot];
+                s.index = Some(index);
+                Handle {
+                    index: slot,
+                    generation: s.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    index: Some(index),
+                });
+                Handle {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        };
+
+        self.elems.push(OwnedElem {
+            inner: elem,
+            handle,
+        });
+        self.dirty = true;
+        handle
+    }
+
+    ///
+    /// Remove an element. Returns None if the handle is stale.
+    ///
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let index = self.index_of(handle)?;
+
+        let e = self.elems.swap_remove(index);
+        if let Some(moved) = self.elems.get(index) {
+            self.slots[moved.handle.index].index = Some(index);
+        }
+
+        let s = &mut self.slots[handle.index];
+        s.index = None;
+        s.generation += 1;
+        self.free_slots.push(handle.index);
+
+        self.dirty = true;
+        Some(e.inner)
+    }
+
+    #[must_use]
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let index = self.index_of(handle)?;
+        Some(&self.elems[index].inner)
+    }
+
+    ///
+    /// The tree is updated the next time it is queried
+    /// since the aabb of the element may have changed.
+    ///
+    #[must_use]
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        let index = self.index_of(handle)?;
+        self.dirty = true;
+        Some(&mut self.elems[index].inner)
+    }
+
+    ///
+    /// Iterate over every element in an unspecified order.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.elems.iter().map(|x| (x.handle, &x.inner))
+    }
+
+    ///
+    /// Iterate over every element in an unspecified order.
+    /// The tree is updated the next time it is queried
+    /// since the aabbs of the elements may have changed.
+    ///
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.dirty = true;
+        self.elems.iter_mut().map(|x| (x.handle, &mut x.inner))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    ///
+    /// Build the tree from scratch.
+    ///
+    pub fn rebuild(&mut self) {
+        self.data = Tree::new(&mut self.elems).get_tree_data();
+        self.update_slots();
+        self.dirty = false;
+    }
+
+    ///
+    /// Return the elements, dropping the tree.
+    ///
+    pub fn into_vec(self) -> Vec<T> {
+        self.elems.into_iter().map(|x| x.inner).collect()
+    }
+
+    ///
+    /// Return a tree that can be queried, updating it first if
+    /// elements were inserted, removed, or modified since the last call.
+    ///
+    pub fn as_tree(&mut self) -> Tree<'_, OwnedElem<T>> {
+        if self.dirty {
+            let num_levels = num_level::default(self.elems.len());
+
+            let current_levels = compt::dfs_order::CompleteTree::from_preorder(&self.data.nodes)
+                .unwrap()
+                .get_height();
+
+            //Decide whether the dividers can be reused before refitting,
+            //so that the elements are never partitioned twice.
+            if num_levels != current_levels
+                || subtree_len(&refit_lens(&self.elems, &self.data.nodes)).is_none()
+            {
+                self.rebuild();
+            } else {
+                self.data = Tree::refit(&mut self.elems, &self.data).get_tree_data();
+                self.update_slots();
+                self.dirty = false;
+            }
+        }
+
+        Tree::from_tree_data(&mut self.elems, &self.data)
+    }
+
+    fn index_of(&self, handle: Handle) -> Option<usize> {
+        let s = self.slots.get(handle.index)?;
+        if s.generation == handle.generation {
+            s.index
+        } else {
+            None
+        }
+    }
+
+    fn update_slots(&mut self) {
+        for (i, e) in self.elems.iter().enumerate() {
+            self.slots[e.handle.index].index = Some(i);
+        }
+    }
+}
+
+//The number of elements that each node would be left with if the elements
+//were refitted to the dividers of the nodes, in the same order as the nodes.
+fn refit_lens<T: Aabb>(elems: &[T], nodes: &[NodeData<T::Num>]) -> Vec<usize> {
+    let mut lens = vec![0; nodes.len()];
+    for e in elems {
+        let mut index = 0;
+        let mut nodes = nodes;
+        loop {
+            let (node, rest) = nodes.split_first().unwrap();
+            let div = match node.div {
+                Some(div) if !rest.is_empty() => div,
+                _ => break,
+            };
+
+            let (left, right) = rest.split_at(rest.len() / 2);
+            let r = e.get().get_range(node.axis);
+            if r.contains(div) {
+                break;
+            } else if r.end < div {
+                index += 1;
+                nodes = left;
+            } else {
+                index += 1 + left.len();
+                nodes = right;
+            }
+        }
+        lens[index] += 1;
+    }
+    lens
+}
+
+//Returns the number of elements in the subtree,
+//or None if there is a node whose children are too unevenly filled.
+fn subtree_len(lens: &[usize]) -> Option<usize> {
+    let (len, rest) = lens.split_first().unwrap();
+    if rest.is_empty() {
+        return Some(*len);
+    }
+
+    let (left, right) = rest.split_at(rest.len() / 2);
+    let left = subtree_len(left)?;
+    let right = subtree_len(right)?;
+
+    let (small, big) = (left.min(right), left.max(right));
+    if big > num_level::DEFAULT_NUMBER_ELEM_PER_NODE && small * REBALANCE_RATIO < big {
+        None
+    } else {
+        Some(len + left + right)
+    }
+}
@@ -0,0 +1,205 @@
+//!
+//! Continuous collision detection with swept rects.
+//!
+//! Elements that move far in one frame can pass through each other without their
+//! rects ever intersecting at the end of a frame. Here the tree is built from the
+//! union of every element's rect at the start and at the end of the frame instead,
+//! and a [`TimeOfImpact`] decides when, if ever, each candidate pair actually touched.
+//!
+//! ```rust
+//! use broccoli::queries::ccd::{find_impacts, AabbTimeOfImpact};
+//! use broccoli::rect;
+//!
+//! //A thin wall and a bullet that moves from one side of it to the other in one frame.
+//! let mut aabbs = [
+//!     (rect(50.0, 51.0, 0.0, 100.0), rect(50.0, 51.0, 0.0, 100.0)),
+//!     (rect(0.0, 2.0, 10.0, 12.0), rect(100.0, 102.0, 10.0, 12.0)),
+//! ];
+//!
+//! assert!(!aabbs[0].0.intersects_rect(&aabbs[1].0));
+//!
+//! let impacts = find_impacts(&mut aabbs, AabbTimeOfImpact(|a: &(_, _)| a.1));
+//! assert_eq!(impacts.len(), 1);
+//! assert_eq!(impacts[0].time, 0.49);
+//! ```
+//!
+
+use super::*;
+
+///
+/// The narrow phase of continuous collision detection.
+///
+pub trait TimeOfImpact<T: Aabb> {
+    ///Such as the fraction of the frame that passed before two elements touched.
+    type Time: PartialOrd;
+
+    ///The rect of the element at the start of the frame.
+    ///Its rect at the end of the frame is its current one.
+    fn prev_rect(&mut self, a: &T) -> Rect<T::Num>;
+
+    ///Return when the two elements first touched during the frame, or None if they
+    ///never did. Only called on pairs whose swept rects intersect.
+    fn time_of_impact(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) -> Option<Self::Time>;
+}
+
+///
+/// Treats every element as its rect moving in a straight line from its
+/// previous rect, returned by the closure, to its current one.
+/// The time is the fraction of the frame from zero to one.
+///
+pub struct AabbTimeOfImpact<F>(pub F);
+
+impl<T: Aabb, F> TimeOfImpact<T> for AabbTimeOfImpact<F>
+where
+    T::Num: num_traits::Float,
+    F: FnMut(&T) -> Rect<T::Num>,
+{
+    type Time = T::Num;
+
+    fn prev_rect(&mut self, a: &T) -> Rect<T::Num> {
+        (self.0)(a)
+    }
+
+    fn time_of_impact(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) -> Option<T::Num> {
+        use num_traits::{One, Zero};
+
+        let (a0, b0) = ((self.0)(&a), (self.0)(&b));
+        let (a1, b1) = (a.get(), b.get());
+
+        //Every endpoint moves linearly, so each way for the two to be apart along
+        //an axis holds up to or from some time. Find when none of them hold.
+        let mut lo = T::Num::zero();
+        let mut hi = T::Num::one();
+        for axis in [AxisDyn::X, AxisDyn::Y] {
+            let (ra0, ra1) = (a0.get_range(axis), a1.get_range(axis));
+            let (rb0, rb1) = (b0.get_range(axis), b1.get_range(axis));
+
+            for (c, d) in [
+                (
+                    rb0.end - ra0.start,
+                    (rb1.end - rb0.end) - (ra1.start - ra0.start),
+                ),
+                (
+                    ra0.end - rb0.start,
+                    (ra1.end - ra0.end) - (rb1.start - rb0.start),
+                ),
+            ] {
+                //Need c + d*t >= 0
+                if d.is_zero() {
+                    if c < T::Num::zero() {
+                        return None;
+                    }
+                } else if d > T::Num::zero() {
+                    lo = num_traits::Float::max(lo, -c / d);
+                } else {
+                    hi = num_traits::Float::min(hi, -c / d);
+                }
+            }
+        }
+
+        if lo <= hi {
+            Some(lo)
+        } else {
+            None
+        }
+    }
+}
+
+struct TimeOfImpactClosure<A, B> {
+    prev: A,
+    toi: B,
+}
+
+impl<T: Aabb, A, B, N> TimeOfImpact<T> for TimeOfImpactClosure<A, B>
+where
+    A: FnMut(&T) -> Rect<T::Num>,
+    B: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> Option<N>,
+    N: PartialOrd,
+{
+    type Time = N;
+
+    fn prev_rect(&mut self, a: &T) -> Rect<T::Num> {
+        (self.prev)(a)
+    }
+
+    fn time_of_impact(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) -> Option<N> {
+        (self.toi)(a, b)
+    }
+}
+
+impl<T: Aabb, I: TimeOfImpact<T>> TimeOfImpact<T> for &mut I {
+    type Time = I::Time;
+
+    fn prev_rect(&mut self, a: &T) -> Rect<T::Num> {
+        (*self).prev_rect(a)
+    }
+
+    fn time_of_impact(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) -> Option<I::Time> {
+        (*self).time_of_impact(a, b)
+    }
+}
+
+///
+/// Two elements that touched during the frame.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Impact<N> {
+    ///The index of the first element. Always less than `b`.
+    pub a: usize,
+    ///The index of the second element.
+    pub b: usize,
+    pub time: N,
+}
+
+///
+/// Find every pair of elements that touched during the frame, sorted by
+/// the earliest time of impact. The elements are not reordered, so they
+/// do not need to be [`ManySwap`].
+///
+pub fn find_impacts<T: Aabb, I: TimeOfImpact<T>>(
+    bots: &mut [T],
+    mut toi: I,
+) -> Vec<Impact<I::Time>> {
+    let mut swept: Vec<_> = bots
+        .iter_mut()
+        .enumerate()
+        .map(|(i, a)| {
+            let mut rect = toi.prev_rect(a);
+            rect.grow_to_fit(a.get());
+            ManySwappable(BBox::new(rect, (i, a)))
+        })
+        .collect();
+
+    let mut impacts = vec![];
+    Tree::new(&mut swept).find_colliding_pairs(|a, b| {
+        let ((i, a), (j, b)) = (a.unpack_inner(), b.unpack_inner());
+        let (i, a, j, b) = if i < j {
+            (*i, a, *j, b)
+        } else {
+            (*j, b, *i, a)
+        };
+
+        if let Some(time) = toi.time_of_impact(AabbPin::from_mut(a), AabbPin::from_mut(b)) {
+            impacts.push(Impact { a: i, b: j, time });
+        }
+    });
+
+    impacts.sort_by(|x, y| {
+        x.time
+            .partial_cmp(&y.time)
+            .unwrap_or(Ordering::Equal)
+            .then((x.a, x.b).cmp(&(y.a, y.b)))
+    });
+    impacts
+}
+
+///
+/// Like [`find_impacts()`] except the [`TimeOfImpact`] is made out of closures.
+///
+pub fn find_impacts_closure<T: Aabb, N: PartialOrd>(
+    bots: &mut [T],
+    prev: impl FnMut(&T) -> Rect<T::Num>,
+    toi: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> Option<N>,
+) -> Vec<Impact<N>> {
+    find_impacts(bots, TimeOfImpactClosure { prev, toi })
+}
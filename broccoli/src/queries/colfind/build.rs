@@ -9,6 +9,13 @@ use super::*;
 ///
 pub trait CollisionHandler<T: Aabb> {
     fn collide(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>);
+
+    ///Return true once no more pairs are wanted.
+    ///The search stops soon after, but collide() may still be called a few more times.
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        false
+    }
 }
 impl<T: Aabb, F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>)> CollisionHandler<T> for F {
     #[inline(always)]
@@ -35,14 +42,10 @@ impl<'b, T: Aabb> NodeFinisher<'b, T> {
 /// The main primitive to visit each node and find colliding pairs
 pub struct CollisionVisitor<'a, 'b, T: Aabb> {
     vistr: VistrMutPin<'b, Node<'a, T, T::Num>>,
-    axis: AxisDyn,
 }
 impl<'a, 'b, T: Aabb> CollisionVisitor<'a, 'b, T> {
     pub fn new(vistr: VistrMutPin<'b, Node<'a, T, T::Num>>) -> Self {
-        CollisionVisitor {
-            vistr,
-            axis: default_axis().to_dyn(),
-        }
+        CollisionVisitor { vistr }
     }
 
     pub fn get_height(&self) -> usize {
@@ -57,38 +60,36 @@ impl<'a, 'b, T: Aabb> CollisionVisitor<'a, 'b, T> {
         mut self,
         handler: &mut N,
     ) -> (NodeFinisher<'b, T>, Option<[Self; 2]>) {
-        handler.handle_nodes_under(self.axis, self.vistr.borrow_mut());
+        let axis = self.vistr.borrow().next().0.axis;
+
+        handler.handle_nodes_under(axis, self.vistr.borrow_mut());
 
         let is_leaf = self.get_height() == 1;
 
         let (n, rest) = self.vistr.next();
 
         let fin = NodeFinisher {
-            axis: self.axis,
+            axis,
             bots: n.into_range(),
             is_leaf,
         };
 
         (
             fin,
-            if let Some([left, right]) = rest {
-                Some([
-                    CollisionVisitor {
-                        vistr: left,
-                        axis: self.axis.next(),
-                    },
-                    CollisionVisitor {
-                        vistr: right,
-                        axis: self.axis.next(),
-                    },
-                ])
-            } else {
-                None
-            },
+            rest.map(|[left, right]| {
+                [
+                    CollisionVisitor { vistr: left },
+                    CollisionVisitor { vistr: right },
+                ]
+            }),
         )
     }
 
     pub fn recurse_seq<N: NodeHandler<T>>(self, handler: &mut N) {
+        if handler.is_finished() {
+            return;
+        }
+
         let (n, rest) = self.collide_and_next(handler);
 
         n.finish(handler);
@@ -107,6 +108,12 @@ pub trait NodeHandler<T: Aabb> {
 
     // implementer responsibility to check if it is a leaf or not.
     fn handle_nodes_under(&mut self, this_axis: AxisDyn, m: VistrMutPin<Node<T, T::Num>>);
+
+    ///Return true once no more nodes need to be handled.
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        false
+    }
 }
 
 ///An vec api to avoid excessive dynamic allocation by reusing a Vec
@@ -0,0 +1,362 @@
+//!
+//! Skip pairs of elements that can never interact, such as bullets with other bullets.
+//!
+//! ```rust
+//! use broccoli::aabb::ManySwappable;
+//! use broccoli::queries::colfind::filter::{BitMaskFilter, CollisionMask};
+//! use broccoli::rect;
+//!
+//! const PLAYER: u32 = 1;
+//! const BULLET: u32 = 2;
+//!
+//! let mut aabbs = [
+//!     ManySwappable((rect(0, 10, 0, 10), CollisionMask::new(PLAYER, PLAYER | BULLET))),
+//!     ManySwappable((rect(5, 15, 5, 15), CollisionMask::new(BULLET, PLAYER))),
+//!     ManySwappable((rect(5, 15, 5, 15), CollisionMask::new(BULLET, PLAYER))),
+//! ];
+//!
+//! let mut tree = broccoli::Tree::new(&mut aabbs);
+//!
+//! let filter = BitMaskFilter(|a: &ManySwappable<(_, CollisionMask)>| a.0 .1);
+//!
+//! let mut num = 0;
+//! tree.find_colliding_pairs_filtered(filter, |_, _| num += 1);
+//!
+//! //The two bullets do not collide with each other.
+//! assert_eq!(num, 2);
+//! ```
+//!
+
+use super::*;
+use oned::{DefaultNodeHandler, SubtreeFilter};
+
+///
+/// Decides which pairs of elements are passed to the collision handler.
+///
+/// Every node has a summary of its elements and a summary of the elements
+/// of its whole subtree, so that nodes and subtrees whose summaries can
+/// not collide with each other are skipped without visiting their elements.
+///
+pub trait CollisionFilter<T> {
+    ///A conservative description of a set of elements,
+    ///such as the union of their layers.
+    type Summary: Copy;
+
+    ///The summary of one element.
+    fn summarize(&self, a: &T) -> Self::Summary;
+
+    ///The summary of no elements.
+    fn empty(&self) -> Self::Summary;
+
+    ///The summary of the elements of both summaries.
+    fn union(&self, a: Self::Summary, b: Self::Summary) -> Self::Summary;
+
+    ///Return false if no element described by `a` can
+    ///collide with any element described by `b`.
+    fn may_collide(&self, a: Self::Summary, b: Self::Summary) -> bool;
+
+    ///Return false if the pair should not be passed to the collision handler.
+    #[inline(always)]
+    fn can_collide(&self, a: &T, b: &T) -> bool {
+        self.may_collide(self.summarize(a), self.summarize(b))
+    }
+}
+
+///
+/// The layers an element is on, the layers it collides with,
+/// and the group it belongs to. See [`BitMaskFilter`]
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CollisionMask {
+    pub layers: u32,
+    pub mask: u32,
+    ///Elements of the same group never collide with each other.
+    ///Zero means no group.
+    pub group: u32,
+}
+
+impl CollisionMask {
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(layers: u32, mask: u32) -> Self {
+        CollisionMask {
+            layers,
+            mask,
+            group: 0,
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_group(self, group: u32) -> Self {
+        CollisionMask { group, ..self }
+    }
+
+    ///Combine the layers and masks of both. The group of the result is zero.
+    #[inline(always)]
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        CollisionMask::new(self.layers | other.layers, self.mask | other.mask)
+    }
+
+    ///Returns true if each is on a layer that the other collides with.
+    #[inline(always)]
+    #[must_use]
+    pub const fn interacts(&self, other: &Self) -> bool {
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+}
+
+///
+/// Filter pairs using the [`CollisionMask`] returned by the closure for each element.
+///
+#[derive(Copy, Clone)]
+pub struct BitMaskFilter<F>(pub F);
+
+impl<T, F: Fn(&T) -> CollisionMask> CollisionFilter<T> for BitMaskFilter<F> {
+    type Summary = CollisionMask;
+
+    #[inline(always)]
+    fn summarize(&self, a: &T) -> CollisionMask {
+        (self.0)(a)
+    }
+
+    #[inline(always)]
+    fn empty(&self) -> CollisionMask {
+        CollisionMask::default()
+    }
+
+    #[inline(always)]
+    fn union(&self, a: CollisionMask, b: CollisionMask) -> CollisionMask {
+        a.union(b)
+    }
+
+    #[inline(always)]
+    fn may_collide(&self, a: CollisionMask, b: CollisionMask) -> bool {
+        a.interacts(&b)
+    }
+
+    #[inline(always)]
+    fn can_collide(&self, a: &T, b: &T) -> bool {
+        let (a, b) = ((self.0)(a), (self.0)(b));
+        a.interacts(&b) && (a.group == 0 || a.group != b.group)
+    }
+}
+
+impl<T, F: CollisionFilter<T>> CollisionFilter<T> for &F {
+    type Summary = F::Summary;
+
+    #[inline(always)]
+    fn summarize(&self, a: &T) -> Self::Summary {
+        (*self).summarize(a)
+    }
+
+    #[inline(always)]
+    fn empty(&self) -> Self::Summary {
+        (*self).empty()
+    }
+
+    #[inline(always)]
+    fn union(&self, a: Self::Summary, b: Self::Summary) -> Self::Summary {
+        (*self).union(a, b)
+    }
+
+    #[inline(always)]
+    fn may_collide(&self, a: Self::Summary, b: Self::Summary) -> bool {
+        (*self).may_collide(a, b)
+    }
+
+    #[inline(always)]
+    fn can_collide(&self, a: &T, b: &T) -> bool {
+        (*self).can_collide(a, b)
+    }
+}
+
+impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// Like [`Tree::find_colliding_pairs()`] except pairs that the filter
+    /// rejects are never passed to the closure.
+    ///
+    pub fn find_colliding_pairs_filtered<F: CollisionFilter<T>>(
+        &mut self,
+        filter: F,
+        func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>),
+    ) {
+        let mut summaries = Vec::with_capacity(self.num_nodes());
+        summarize(&filter, self.vistr(), &mut summaries);
+
+        let mut handler = DefaultNodeHandler::new(Filtered {
+            filter: &filter,
+            func,
+        });
+        recurse(self.vistr_mut(), &summaries, &filter, &mut handler);
+    }
+}
+
+struct Filtered<'a, F, C> {
+    filter: &'a F,
+    func: C,
+}
+
+impl<'a, T: Aabb, F: CollisionFilter<T>, C: CollisionHandler<T>> CollisionHandler<T>
+    for Filtered<'a, F, C>
+{
+    #[inline(always)]
+    fn collide(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) {
+        if self.filter.can_collide(&a, &b) {
+            self.func.collide(a, b);
+        }
+    }
+
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        self.func.is_finished()
+    }
+}
+
+//Skips the nodes under an anchor whose summaries can not collide with the anchor's.
+struct Pruner<'s, S, M> {
+    may_collide: &'s M,
+    nodes: &'s [(S, S)],
+}
+
+impl<'s, S: Copy, M: Fn(S) -> bool> SubtreeFilter for Pruner<'s, S, M> {
+    #[inline(always)]
+    fn next(self) -> (bool, bool, Option<[Self; 2]>) {
+        let (&(node, subtree), rest) = self.nodes.split_first().unwrap();
+
+        let children = if rest.is_empty() {
+            None
+        } else {
+            let (left, right) = rest.split_at(rest.len() / 2);
+            Some([
+                Pruner {
+                    may_collide: self.may_collide,
+                    nodes: left,
+                },
+                Pruner {
+                    may_collide: self.may_collide,
+                    nodes: right,
+                },
+            ])
+        };
+
+        (
+            (self.may_collide)(node),
+            (self.may_collide)(subtree),
+            children,
+        )
+    }
+}
+
+//Push the summary of each node and of its subtree in dfs preorder.
+//Returns the summary of the subtree.
+fn summarize<T: Aabb, F: CollisionFilter<T>>(
+    filter: &F,
+    v: Vistr<Node<T, T::Num>>,
+    out: &mut Vec<(F::Summary, F::Summary)>,
+) -> F::Summary {
+    let (nn, rest) = v.next();
+
+    let node = nn.range.iter().fold(filter.empty(), |acc, a| {
+        filter.union(acc, filter.summarize(a))
+    });
+
+    let index = out.len();
+    out.push((node, node));
+
+    if let Some([left, right]) = rest {
+        let left = summarize(filter, left, out);
+        let right = summarize(filter, right, out);
+        out[index].1 = filter.union(node, filter.union(left, right));
+    }
+    out[index].1
+}
+
+fn recurse<T: Aabb, F: CollisionFilter<T>, C: CollisionHandler<T>>(
+    mut m: VistrMutPin<Node<T, T::Num>>,
+    summaries: &[(F::Summary, F::Summary)],
+    filter: &F,
+    handler: &mut DefaultNodeHandler<Filtered<F, C>>,
+) {
+    let (&(node, subtree), rest) = summaries.split_first().unwrap();
+
+    //Nothing in this subtree can collide with anything else in it.
+    if !filter.may_collide(subtree, subtree) {
+        return;
+    }
+
+    let (left_summaries, right_summaries) = rest.split_at(rest.len() / 2);
+
+    let axis = m.borrow().next().0.axis;
+    let is_leaf = m.get_height() == 1;
+
+    let may_collide = |a| filter.may_collide(node, a);
+    handler.handle_nodes_under_filtered(
+        axis,
+        m.borrow_mut(),
+        [
+            Pruner {
+                may_collide: &may_collide,
+                nodes: left_summaries,
+            },
+            Pruner {
+                may_collide: &may_collide,
+                nodes: right_summaries,
+            },
+        ],
+    );
+
+    let (nn, rest) = m.next();
+    if filter.may_collide(node, node) {
+        handler.handle_node(axis, nn.into_range(), is_leaf);
+    }
+
+    if let Some([left, right]) = rest {
+        recurse(left, left_summaries, filter, handler);
+        recurse(right, right_summaries, filter, handler);
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb> Naive<'a, T> {
+        pub fn find_colliding_pairs_filtered<F: CollisionFilter<T>>(
+            &mut self,
+            filter: F,
+            mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>),
+        ) {
+            self.find_colliding_pairs(|a, b| {
+                if filter.can_collide(&a, &b) {
+                    func(a, b);
+                }
+            });
+        }
+    }
+
+    impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_colliding_pairs_filtered<F: CollisionFilter<T>>(&mut self, filter: F) {
+            use crate::assert::into_ptr_usize;
+
+            let mut res_dino = vec![];
+            Tree::new(self.inner).find_colliding_pairs_filtered(&filter, |a, b| {
+                let (a, b) = (into_ptr_usize(a), into_ptr_usize(b));
+                res_dino.push((a.min(b), a.max(b)));
+            });
+
+            let mut res_naive = vec![];
+            Naive::new(self.inner).find_colliding_pairs_filtered(&filter, |a, b| {
+                let (a, b) = (into_ptr_usize(a), into_ptr_usize(b));
+                res_naive.push((a.min(b), a.max(b)));
+            });
+
+            res_dino.sort_unstable();
+            res_naive.sort_unstable();
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+        }
+    }
+}
@@ -6,13 +6,14 @@ use super::tools;
 use super::*;
 pub mod build;
 use build::*;
+pub mod filter;
+pub mod pairs;
 
 mod assert {
     use super::*;
     impl<'a, T: Aabb> Assert<'a, T> {
         ///Panics if a disconnect is detected between all colfind methods.
         pub fn assert_query(&mut self) {
-            let bots = &mut self.inner;
             #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
             pub struct CollisionPtr {
                 inner: Vec<(usize, usize)>,
@@ -32,58 +33,82 @@ mod assert {
                 }
             }
 
-            let mut bots: Vec<_> = bots
+            fn check<E: Aabb + ManySwap>(bots: &mut [E], index: impl Fn(&E) -> usize) {
+                let naive_res = {
+                    let mut cc = CollisionPtr::new();
+                    Naive::new(bots).find_colliding_pairs(|a, b| {
+                        cc.add_pair(index(&a), index(&b));
+                    });
+                    cc.finish();
+                    cc
+                };
+
+                let mut tree = Tree::new(bots);
+
+                let tree_res = {
+                    let mut cc = CollisionPtr::new();
+
+                    tree.find_colliding_pairs(|a, b| {
+                        cc.add_pair(index(&a), index(&b));
+                    });
+                    cc.finish();
+                    cc
+                };
+
+                let pull_res = {
+                    let mut cc = CollisionPtr::new();
+
+                    let mut pairs = tree.colliding_pairs();
+                    while let Some(mut chunk) = pairs.next_chunk() {
+                        while let Some((a, b)) = chunk.next_pair() {
+                            cc.add_pair(index(&a), index(&b));
+                        }
+                    }
+                    cc.finish();
+                    cc
+                };
+
+                // let notsort_res = {
+                //     let mut cc = CollisionPtr::new();
+
+                //     NotSortedTree::new(bots).find_colliding_pairs(|a, b| {
+                //         cc.add_pair(a.0 .1, b.0 .1);
+                //     });
+                //     cc.finish();
+                //     cc
+                // };
+
+                // let sweep_res = {
+                //     let mut cc = CollisionPtr::new();
+                //     SweepAndPrune::new(bots).find_colliding_pairs(|a, b| {
+                //         cc.add_pair(a.0 .1, b.0 .1);
+                //     });
+                //     cc.finish();
+                //     cc
+                // };
+
+                //assert_eq!(naive_res.inner.len(), sweep_res.inner.len());
+                assert_eq!(naive_res.inner.len(), tree_res.inner.len());
+                //assert_eq!(naive_res.inner.len(), notsort_res.inner.len());
+
+                assert_eq!(naive_res, tree_res);
+                assert_eq!(naive_res, pull_res);
+                assert_eq!(naive_res.inner.len(), tree.count_colliding_pairs());
+                //assert_eq!(naive_res, sweep_res);
+                //assert_eq!(naive_res, notsort_res);
+            }
+
+            let mut bots: Vec<_> = self
+                .inner
                 .iter_mut()
                 .enumerate()
                 .map(|(i, x)| ManySwappable((*x.get(), i)))
                 .collect();
-            let bots = bots.as_mut_slice();
-
-            let naive_res = {
-                let mut cc = CollisionPtr::new();
-                Naive::new(bots).find_colliding_pairs(|a, b| {
-                    cc.add_pair(a.0 .1, b.0 .1);
-                });
-                cc.finish();
-                cc
-            };
-
-            let tree_res = {
-                let mut cc = CollisionPtr::new();
-
-                Tree::new(bots).find_colliding_pairs(|a, b| {
-                    cc.add_pair(a.0 .1, b.0 .1);
-                });
-                cc.finish();
-                cc
-            };
-
-            // let notsort_res = {
-            //     let mut cc = CollisionPtr::new();
-
-            //     NotSortedTree::new(bots).find_colliding_pairs(|a, b| {
-            //         cc.add_pair(a.0 .1, b.0 .1);
-            //     });
-            //     cc.finish();
-            //     cc
-            // };
-
-            // let sweep_res = {
-            //     let mut cc = CollisionPtr::new();
-            //     SweepAndPrune::new(bots).find_colliding_pairs(|a, b| {
-            //         cc.add_pair(a.0 .1, b.0 .1);
-            //     });
-            //     cc.finish();
-            //     cc
-            // };
-
-            //assert_eq!(naive_res.inner.len(), sweep_res.inner.len());
-            assert_eq!(naive_res.inner.len(), tree_res.inner.len());
-            //assert_eq!(naive_res.inner.len(), notsort_res.inner.len());
-
-            assert_eq!(naive_res, tree_res);
-            //assert_eq!(naive_res, sweep_res);
-            //assert_eq!(naive_res, notsort_res);
+            check(&mut bots, |a| a.0 .1);
+
+            //The same rects except ones that only touch do not collide.
+            let mut bots: Vec<_> = bots.into_iter().map(HalfOpen).collect();
+            check(&mut bots, |a| a.0 .0 .1);
         }
     }
 
@@ -93,7 +118,7 @@ mod assert {
             mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>),
         ) {
             queries::for_every_pair(self.inner.borrow_mut(), move |a, b| {
-                if a.get().intersects_rect(b.get()) {
+                if T::INTERSECTION_MODE.intersects_rect(a.get(), b.get()) {
                     func(a, b);
                 }
             });
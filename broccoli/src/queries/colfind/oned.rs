@@ -2,6 +2,7 @@ use twounordered::TwoUnorderedVecs;
 
 use super::CollisionHandler;
 use super::*;
+use core::ops::ControlFlow;
 
 //For sweep and prune type algorithms, we can narrow down which bots
 //intersection in one dimension. We also need to check the other direction
@@ -20,10 +21,15 @@ impl<'a, A: Axis + 'a, T: Aabb, F: CollisionHandler<T> + 'a> CollisionHandler<T>
         //only check if the opoosite axis intersects.
         //already know they intersect
         let a2 = self.axis.next();
-        if a.range(a2).intersects(b.range(a2)) {
+        if T::INTERSECTION_MODE.intersects(a.range(a2), b.range(a2)) {
             self.a.collide(a, b);
         }
     }
+
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        self.a.is_finished()
+    }
 }
 
 pub fn sweep_and_prune<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
@@ -45,7 +51,9 @@ fn find_2d<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
     func: &mut F,
     check_y: bool,
 ) {
-    if check_y {
+    //Elements of a non leaf node that only touch along the divider
+    //do not intersect if they are half open, so they have to be checked as well.
+    if check_y || !T::INTERSECTION_MODE.is_closed() {
         let mut b: OtherAxisCollider<A, _> = OtherAxisCollider { a: func, axis };
         self::find_iter(buffer, axis, bots, &mut b);
     } else {
@@ -88,14 +96,15 @@ fn find_perp_2d1_once<A: Axis, T: Aabb>(
     mut r2: AabbPin<&mut [T]>,
     mut func: impl CollisionHandler<T>,
 ) {
+    let mode = T::INTERSECTION_MODE;
     for y2 in r2.borrow_mut() {
         //Exploit the sorted property, to exit early
-        if y.range(axis).end < y2.range(axis).start {
+        if mode.is_before(y.range(axis).end, y2.range(axis).start) {
             break;
         }
 
         //Because we didnt exit from the previous comparison, we only need to check one thing.
-        if y.range(axis).start <= y2.range(axis).end {
+        if !mode.is_before(y2.range(axis).end, y.range(axis).start) {
             func.collide(y.borrow_mut(), y2);
         }
     }
@@ -123,12 +132,22 @@ fn find_iter<'a, A: Axis, T: Aabb + 'a, F: CollisionHandler<T>>(
     //    Add the new item itself to the activeList and continue with the next item
     //     in the axisList.
 
-    collision_botids.iter_mut().for_each(|mut curr_bot| {
+    let mode = T::INTERSECTION_MODE;
+    for mut curr_bot in collision_botids.iter_mut() {
+        if func.is_finished() {
+            return;
+        }
+
         active.retain_mut_unordered(|that_bot| {
             let crr = curr_bot.range(axis);
 
-            if that_bot.range(axis).end >= crr.start {
-                debug_assert!(curr_bot.range(axis).intersects(that_bot.range(axis)));
+            if !mode.is_before(that_bot.range(axis).end, crr.start) {
+                //A half open range that is empty does not intersect
+                //the ranges that start where it does.
+                if !mode.is_closed() && mode.is_before(crr.end, that_bot.range(axis).start) {
+                    return true;
+                }
+                debug_assert!(mode.intersects(curr_bot.range(axis), that_bot.range(axis)));
 
                 /*
                 assert!(curr_bot
@@ -144,7 +163,7 @@ fn find_iter<'a, A: Axis, T: Aabb + 'a, F: CollisionHandler<T>>(
         });
 
         active.push(curr_bot);
-    });
+    }
 }
 
 /*
@@ -259,6 +278,7 @@ fn find_other_parallel4<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
     func: &mut F,
 ) {
     use twounordered::RetainMutUnordered;
+    let mode = T::INTERSECTION_MODE;
     let mut xiter = cols.0.into_iter();
     let mut yiter = cols.1.into_iter();
 
@@ -332,8 +352,12 @@ fn find_other_parallel4<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
         match val {
             NextP::X(mut x) => {
                 active_lists.second().retain_mut_unordered(|y| {
-                    if y.range(axis).end >= x.range(axis).start {
-                        func.collide(x.borrow_mut(), y.borrow_mut());
+                    if !mode.is_before(y.range(axis).end, x.range(axis).start) {
+                        //A half open range that is empty does not intersect
+                        //the ranges that start where it does.
+                        if mode.is_closed() || mode.intersects(x.range(axis), y.range(axis)) {
+                            func.collide(x.borrow_mut(), y.borrow_mut());
+                        }
                         true
                     } else {
                         false
@@ -342,9 +366,9 @@ fn find_other_parallel4<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
                 ycounter = 0;
 
                 if xcounter > PRUNE_PERIOD {
-                    active_lists
-                        .first()
-                        .retain_mut_unordered(|x2| x2.range(axis).end >= x.range(axis).start);
+                    active_lists.first().retain_mut_unordered(|x2| {
+                        !mode.is_before(x2.range(axis).end, x.range(axis).start)
+                    });
                     xcounter = 0;
                 } else {
                     xcounter += 1;
@@ -354,8 +378,12 @@ fn find_other_parallel4<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
             }
             NextP::Y(mut y) => {
                 active_lists.first().retain_mut_unordered(|x| {
-                    if x.range(axis).end >= y.range(axis).start {
-                        func.collide(x.borrow_mut(), y.borrow_mut());
+                    if !mode.is_before(x.range(axis).end, y.range(axis).start) {
+                        //A half open range that is empty does not intersect
+                        //the ranges that start where it does.
+                        if mode.is_closed() || mode.intersects(x.range(axis), y.range(axis)) {
+                            func.collide(x.borrow_mut(), y.borrow_mut());
+                        }
                         true
                     } else {
                         false
@@ -364,9 +392,9 @@ fn find_other_parallel4<'a, A: Axis, T: Aabb, F: CollisionHandler<T>>(
                 xcounter = 0;
 
                 if ycounter > PRUNE_PERIOD {
-                    active_lists
-                        .second()
-                        .retain_mut_unordered(|y2| y2.range(axis).end >= y.range(axis).start);
+                    active_lists.second().retain_mut_unordered(|y2| {
+                        !mode.is_before(y2.range(axis).end, y.range(axis).start)
+                    });
                     ycounter = 0;
                 } else {
                     ycounter += 1;
@@ -520,25 +548,60 @@ where
     }
 
     fn handle_nodes_under(&mut self, this_axis: AxisDyn, m: VistrMutPin<Node<T, T::Num>>) {
-        {
-            let (nn, rest) = m.next();
-
-            if let Some([mut left, mut right]) = rest {
-                if let Some(div) = nn.div {
-                    let d = nn.into_node_ref();
-                    let mut g = InnerRecurser {
-                        anchor: DNode {
-                            div,
-                            cont: d.cont,
-                            range: d.range,
-                        },
-                        anchor_axis: this_axis,
-                        handler: self,
-                    };
-
-                    g.recurse(this_axis.next(), left.borrow_mut(), true);
-                    g.recurse(this_axis.next(), right.borrow_mut(), false);
-                }
+        self.handle_nodes_under_filtered(this_axis, m, [CheckAll, CheckAll]);
+    }
+
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        self.coll_handler.is_finished()
+    }
+}
+
+///
+/// Decides which nodes under an anchor node the anchor is checked against.
+///
+pub(crate) trait SubtreeFilter: Sized {
+    ///Returns whether to check the current node, whether to check any node
+    ///in the current subtree at all, and the filters of the children.
+    fn next(self) -> (bool, bool, Option<[Self; 2]>);
+}
+
+///Check the anchor against every node under it.
+pub(crate) struct CheckAll;
+
+impl SubtreeFilter for CheckAll {
+    #[inline(always)]
+    fn next(self) -> (bool, bool, Option<[Self; 2]>) {
+        (true, true, Some([CheckAll, CheckAll]))
+    }
+}
+
+impl<C> DefaultNodeHandler<C> {
+    pub(crate) fn handle_nodes_under_filtered<T: Aabb, P: SubtreeFilter>(
+        &mut self,
+        this_axis: AxisDyn,
+        m: VistrMutPin<Node<T, T::Num>>,
+        [left_filter, right_filter]: [P; 2],
+    ) where
+        C: CollisionHandler<T>,
+    {
+        let (nn, rest) = m.next();
+
+        if let Some([mut left, mut right]) = rest {
+            if let Some(div) = nn.div {
+                let d = nn.into_node_ref();
+                let mut g = InnerRecurser {
+                    anchor: DNode {
+                        div,
+                        cont: d.cont,
+                        range: d.range,
+                    },
+                    anchor_axis: this_axis,
+                    handler: self,
+                };
+
+                g.recurse(left.borrow_mut(), left_filter, true);
+                g.recurse(right.borrow_mut(), right_filter, false);
             }
         }
     }
@@ -548,6 +611,66 @@ impl<'a, T: Aabb> Tree<'a, T> {
     pub fn find_colliding_pairs(&mut self, func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>)) {
         CollisionVisitor::new(self.vistr_mut()).recurse_seq(&mut DefaultNodeHandler::new(func));
     }
+
+    ///
+    /// Like [`Tree::find_colliding_pairs()`] except the search stops
+    /// as soon as the closure returns [`ControlFlow::Break`].
+    ///
+    /// ```
+    /// use broccoli::rect;
+    /// use core::ops::ControlFlow;
+    ///
+    /// let mut aabbs = [rect(0, 10, 0, 10), rect(5, 15, 5, 15), rect(5, 15, 5, 15)];
+    ///
+    /// let mut tree = broccoli::Tree::new(&mut aabbs);
+    ///
+    /// let mut num = 0;
+    /// let res = tree.try_find_colliding_pairs(|_, _| {
+    ///     num += 1;
+    ///     ControlFlow::Break(num)
+    /// });
+    ///
+    /// assert_eq!(res, ControlFlow::Break(1));
+    /// assert_eq!(num, 1);
+    /// ```
+    ///
+    pub fn try_find_colliding_pairs<B>(
+        &mut self,
+        func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        let mut handler = DefaultNodeHandler::new(TryClosure { func, res: None });
+        CollisionVisitor::new(self.vistr_mut()).recurse_seq(&mut handler);
+
+        match handler.coll_handler.res {
+            Some(b) => ControlFlow::Break(b),
+            None => ControlFlow::Continue(()),
+        }
+    }
+}
+
+//Remembers the first break and ignores every pair after it.
+struct TryClosure<F, B> {
+    func: F,
+    res: Option<B>,
+}
+
+impl<T: Aabb, F, B> CollisionHandler<T> for TryClosure<F, B>
+where
+    F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+{
+    #[inline(always)]
+    fn collide(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) {
+        if self.res.is_none() {
+            if let ControlFlow::Break(b) = (self.func)(a, b) {
+                self.res = Some(b);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn is_finished(&self) -> bool {
+        self.res.is_some()
+    }
 }
 
 struct InnerRecurser<'a, T, N, C> {
@@ -557,36 +680,49 @@ struct InnerRecurser<'a, T, N, C> {
 }
 
 impl<'a, T: Aabb, C: CollisionHandler<T>> InnerRecurser<'a, T, T::Num, C> {
-    fn recurse(&mut self, this_axis: AxisDyn, m: VistrMutPin<Node<T, T::Num>>, is_left: bool) {
+    fn recurse<P: SubtreeFilter>(
+        &mut self,
+        m: VistrMutPin<Node<T, T::Num>>,
+        filter: P,
+        is_left: bool,
+    ) {
         let anchor_axis = self.anchor_axis;
 
-        let (mut nn, rest) = m.next();
+        let (check_node, check_subtree, filter_rest) = filter.next();
+        if !check_subtree || self.handler.coll_handler.is_finished() {
+            return;
+        }
 
-        handle_children(
-            &mut self.handler.prevec,
-            &mut self.handler.coll_handler,
-            HandleChildrenArgs {
-                anchor: self.anchor.borrow(),
-                anchor_axis: self.anchor_axis,
-                current: nn.borrow_mut().into_node_ref(),
-                current_axis: this_axis,
-            },
-            is_left,
-        );
+        let (mut nn, rest) = m.next();
+        let this_axis = nn.axis;
+
+        if check_node {
+            handle_children(
+                &mut self.handler.prevec,
+                &mut self.handler.coll_handler,
+                HandleChildrenArgs {
+                    anchor: self.anchor.borrow(),
+                    anchor_axis: self.anchor_axis,
+                    current: nn.borrow_mut().into_node_ref(),
+                    current_axis: this_axis,
+                },
+                is_left,
+            );
+        }
 
-        if let Some([left, right]) = rest {
+        if let (Some([left, right]), Some([left_filter, right_filter])) = (rest, filter_rest) {
             if let Some(div) = nn.div {
                 if anchor_axis.is_equal_to(this_axis) {
                     match is_left {
                         true => {
                             if div < self.anchor.cont.start {
-                                self.recurse(this_axis.next(), right, is_left);
+                                self.recurse(right, right_filter, is_left);
                                 return;
                             }
                         }
                         false => {
                             if div >= self.anchor.cont.end {
-                                self.recurse(this_axis.next(), left, is_left);
+                                self.recurse(left, left_filter, is_left);
                                 return;
                             }
                         }
@@ -594,8 +730,8 @@ impl<'a, T: Aabb, C: CollisionHandler<T>> InnerRecurser<'a, T, T::Num, C> {
                 }
             }
 
-            self.recurse(this_axis.next(), left, is_left);
-            self.recurse(this_axis.next(), right, is_left);
+            self.recurse(left, left_filter, is_left);
+            self.recurse(right, right_filter, is_left);
         }
     }
 }
@@ -652,6 +788,9 @@ fn handle_children<T: Aabb, F>(
         if is_left {
             //iterate over current nodes botd
             for y in r1.iter_mut() {
+                if func.is_finished() {
+                    return;
+                }
                 let r2 = r2.borrow_mut();
 
                 oned::find_perp_2d1_once(
@@ -659,7 +798,7 @@ fn handle_children<T: Aabb, F>(
                     y,
                     r2,
                     |a: AabbPin<&mut T>, b: AabbPin<&mut T>| {
-                        if a.range(axis).end >= b.range(axis).start {
+                        if !T::INTERSECTION_MODE.is_before(a.range(axis).end, b.range(axis).start) {
                             func.collide(a, b);
                         }
                     },
@@ -668,6 +807,9 @@ fn handle_children<T: Aabb, F>(
         } else {
             //iterate over current nodes botd
             for y in r1.iter_mut() {
+                if func.is_finished() {
+                    return;
+                }
                 let r2 = r2.borrow_mut();
 
                 oned::find_perp_2d1_once(
@@ -675,7 +817,7 @@ fn handle_children<T: Aabb, F>(
                     y,
                     r2,
                     |a: AabbPin<&mut T>, b: AabbPin<&mut T>| {
-                        if a.range(axis).start <= b.range(axis).end {
+                        if !T::INTERSECTION_MODE.is_before(b.range(axis).end, a.range(axis).start) {
                             func.collide(a, b);
                         }
                     },
@@ -699,14 +841,14 @@ fn handle_children<T: Aabb, F>(
         if is_left {
             if f.anchor.cont.start <= current2.cont.end {
                 fb.build(|a, b| {
-                    if a.range(axis).start <= b.range(axis).end {
+                    if !T::INTERSECTION_MODE.is_before(b.range(axis).end, a.range(axis).start) {
                         func.collide(a, b)
                     }
                 });
             }
         } else if f.anchor.cont.end >= current2.cont.start {
             fb.build(|a, b| {
-                if a.range(axis).end >= b.range(axis).start {
+                if !T::INTERSECTION_MODE.is_before(a.range(axis).end, b.range(axis).start) {
                     func.collide(a, b)
                 }
             });
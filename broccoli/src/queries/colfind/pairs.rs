@@ -0,0 +1,197 @@
+//!
+//! Pull colliding pairs out of a tree a chunk at a time instead of passing a closure.
+//!
+//! ```rust
+//! use broccoli::rect;
+//!
+//! let mut aabbs = [rect(0, 10, 0, 10), rect(5, 15, 5, 15), rect(5, 15, 5, 15)];
+//!
+//! let mut tree = broccoli::Tree::new(&mut aabbs);
+//!
+//! let mut num = 0;
+//! let mut pairs = tree.colliding_pairs();
+//! while let Some(mut chunk) = pairs.next_chunk() {
+//!     while let Some((a, b)) = chunk.next_pair() {
+//!         assert!(a.intersects_rect(&b));
+//!         num += 1;
+//!     }
+//! }
+//! assert_eq!(num, 3);
+//! ```
+//!
+
+use super::*;
+use oned::DefaultNodeHandler;
+
+impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// Like [`Tree::find_colliding_pairs()`] except the pairs are pulled
+    /// out of the returned [`CollidingPairs`] a chunk at a time.
+    ///
+    /// Panics if the elements are zero sized.
+    ///
+    pub fn colliding_pairs(&mut self) -> CollidingPairs<'a, '_, T> {
+        //The elements of a pair are told apart by their address.
+        assert!(
+            core::mem::size_of::<T>() != 0,
+            "zero sized elements do not have a position"
+        );
+        CollidingPairs::new(&mut self.nodes)
+    }
+}
+
+///
+/// Finds the colliding pairs of a tree lazily, one node at a time.
+///
+/// Every call to [`CollidingPairs::next_chunk()`] resumes the search where
+/// the last one stopped and returns the pairs of the next node that has any.
+/// Only the pairs of one node are buffered at a time.
+/// The pairs come out in the same order as with [`Tree::find_colliding_pairs()`].
+///
+pub struct CollidingPairs<'a, 'b, T: Aabb> {
+    nodes: &'b mut [Node<'a, T, T::Num>],
+    //The start and length in dfs preorder of the subtrees left to visit.
+    stack: Vec<(usize, usize)>,
+    handler: DefaultNodeHandler<Recorder>,
+    //The address and index of every non empty node of the current subtree.
+    starts: Vec<(usize, usize)>,
+    //The node and the index into it of both elements of every pair.
+    pairs: Vec<[(usize, usize); 2]>,
+}
+
+impl<'a, 'b, T: Aabb> CollidingPairs<'a, 'b, T> {
+    fn new(nodes: &'b mut [Node<'a, T, T::Num>]) -> Self {
+        let stack = if nodes.is_empty() {
+            vec![]
+        } else {
+            vec![(0, nodes.len())]
+        };
+
+        CollidingPairs {
+            nodes,
+            stack,
+            handler: DefaultNodeHandler::new(Recorder(vec![])),
+            starts: vec![],
+            pairs: vec![],
+        }
+    }
+
+    ///
+    /// Find the pairs of the next node that has any.
+    /// Returns None once every pair has been found.
+    ///
+    pub fn next_chunk(&mut self) -> Option<PairChunk<'a, '_, T>> {
+        loop {
+            let (start, len) = self.stack.pop()?;
+            if len > 1 {
+                let half = (len - 1) / 2;
+                self.stack.push((start + 1 + half, half));
+                self.stack.push((start + 1, half));
+            }
+
+            let subtree = &mut self.nodes[start..start + len];
+
+            self.handler.coll_handler.0.clear();
+            let tree = compt::dfs_order::CompleteTreeMut::from_preorder_mut(subtree).unwrap();
+            let (fin, _) = CollisionVisitor::new(VistrMutPin::new(tree.vistr_mut()))
+                .collide_and_next(&mut self.handler);
+            fin.finish(&mut self.handler);
+
+            if self.handler.coll_handler.0.is_empty() {
+                continue;
+            }
+
+            let subtree = &mut self.nodes[start..start + len];
+
+            //Elements are never moved by the search, so their addresses
+            //tell which node they are in and where.
+            self.starts.clear();
+            self.starts.extend(
+                subtree
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, n)| !n.range.is_empty())
+                    .map(|(i, n)| (n.range.as_ptr() as usize, i)),
+            );
+            self.starts.sort_unstable();
+
+            let starts = &self.starts;
+            let find = |addr: usize| {
+                let (node_start, node) = starts[starts.partition_point(|&(s, _)| s <= addr) - 1];
+                (node, (addr - node_start) / core::mem::size_of::<T>())
+            };
+
+            self.pairs.clear();
+            self.pairs.extend(
+                self.handler
+                    .coll_handler
+                    .0
+                    .iter()
+                    .map(|&[a, b]| [find(a), find(b)]),
+            );
+
+            return Some(PairChunk {
+                nodes: subtree,
+                pairs: self.pairs.iter(),
+            });
+        }
+    }
+}
+
+///
+/// The pairs found at one node of the tree. See [`CollidingPairs`]
+///
+pub struct PairChunk<'a, 'b, T: Aabb> {
+    nodes: &'b mut [Node<'a, T, T::Num>],
+    pairs: core::slice::Iter<'b, [(usize, usize); 2]>,
+}
+
+impl<'a, 'b, T: Aabb> PairChunk<'a, 'b, T> {
+    ///
+    /// The next pair of this chunk, if any.
+    ///
+    pub fn next_pair(&mut self) -> Option<(AabbPin<&mut T>, AabbPin<&mut T>)> {
+        let &[a, b] = self.pairs.next()?;
+
+        let swapped = b < a;
+        let (x, y) = if swapped { (b, a) } else { (a, b) };
+
+        let (first, second) = if x.0 == y.0 {
+            let (left, right) = self.nodes[x.0].range.borrow_mut().split_at_mut(y.1);
+            (left.get_index_mut(x.1), right.get_index_mut(0))
+        } else {
+            let (left, right) = self.nodes.split_at_mut(y.0);
+            (
+                left[x.0].range.borrow_mut().get_index_mut(x.1),
+                right[0].range.borrow_mut().get_index_mut(y.1),
+            )
+        };
+
+        Some(if swapped {
+            (second, first)
+        } else {
+            (first, second)
+        })
+    }
+
+    ///
+    /// The number of pairs left in this chunk.
+    ///
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.len() == 0
+    }
+}
+
+struct Recorder(Vec<[usize; 2]>);
+
+impl<T: Aabb> CollisionHandler<T> for Recorder {
+    #[inline(always)]
+    fn collide(&mut self, a: AabbPin<&mut T>, b: AabbPin<&mut T>) {
+        use crate::assert::into_ptr_usize;
+        self.0.push([into_ptr_usize(a), into_ptr_usize(b)]);
+    }
+}
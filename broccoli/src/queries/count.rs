@@ -0,0 +1,362 @@
+//!
+//! Count elements or pairs without visiting them one at a time where possible.
+//!
+//! ```rust
+//! use broccoli::queries::knearest::AabbKnearest;
+//! use broccoli::rect;
+//!
+//! let mut aabbs = [rect(0, 10, 0, 10), rect(5, 15, 5, 15), rect(50, 60, 50, 60)];
+//!
+//! let mut tree = broccoli::Tree::new(&mut aabbs);
+//!
+//! assert_eq!(tree.count_in_rect(&rect(0, 20, 0, 20)), 2);
+//! assert_eq!(tree.count_intersect_rect(&rect(12, 55, 12, 55)), 2);
+//! assert_eq!(tree.count_in_radius(axgeom::vec2(15, 20), 5 * 5, AabbKnearest), 1);
+//! assert_eq!(tree.count_colliding_pairs(), 1);
+//! ```
+//!
+
+use super::*;
+use knearest::Knearest;
+use radius::{consider, reach};
+use tools::get_section;
+
+impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// The number of elements that the rect contains. See [`Tree::find_all_in_rect()`]
+    ///
+    #[must_use]
+    pub fn count_in_rect(&self, rect: &Rect<T::Num>) -> usize {
+        RectCounter::in_rect(self.get_nodes(), *rect).count_seq()
+    }
+
+    ///
+    /// The number of elements that intersect the rect. See [`Tree::find_all_intersect_rect()`]
+    ///
+    #[must_use]
+    pub fn count_intersect_rect(&self, rect: &Rect<T::Num>) -> usize {
+        RectCounter::intersect_rect(self.get_nodes(), *rect).count_seq()
+    }
+
+    ///
+    /// The number of elements within the radius of the point.
+    /// See [`Tree::find_all_in_radius()`]
+    ///
+    pub fn count_in_radius(
+        &mut self,
+        point: Vec2<T::Num>,
+        radius: T::Num,
+        mut ktrait: impl Knearest<T>,
+    ) -> usize {
+        RadiusCounter::new(self.vistr_mut(), point, radius).count_seq(&mut ktrait)
+    }
+
+    ///
+    /// The number of pairs that [`Tree::find_colliding_pairs()`] would find.
+    ///
+    /// Unlike the other counts, every pair is still found one at a time,
+    /// so this is only as fast as finding the pairs without handling them.
+    ///
+    pub fn count_colliding_pairs(&mut self) -> usize {
+        let mut num = 0;
+        self.find_colliding_pairs(|_, _| num += 1);
+        num
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Query {
+    In,
+    Intersect(IntersectionMode),
+}
+
+impl Query {
+    //The mode that every element found is at least intersecting the rect in.
+    #[inline(always)]
+    fn mode(self) -> IntersectionMode {
+        match self {
+            Query::In => IntersectionMode::Closed,
+            Query::Intersect(mode) => mode,
+        }
+    }
+}
+
+///
+/// Counts the elements of a subtree that are in or intersect a rect.
+///
+/// Whole nodes and subtrees whose elements are known to be in the rect
+/// from their container range and the dividers above them are
+/// counted by their length instead of element by element.
+///
+pub struct RectCounter<'a, 'b, T: Aabb> {
+    //A complete subtree in dfs preorder.
+    nodes: &'b [Node<'a, T, T::Num>],
+    rect: Rect<T::Num>,
+    query: Query,
+    //Every element of the subtree is strictly within the dividers above it.
+    region: Rect<Option<T::Num>>,
+}
+
+impl<'a, 'b, T: Aabb> RectCounter<'a, 'b, T> {
+    ///
+    /// Count the elements that the rect contains.
+    /// The nodes are those of a whole tree in dfs preorder, see [`Tree::get_nodes()`]
+    ///
+    pub fn in_rect(nodes: &'b [Node<'a, T, T::Num>], rect: Rect<T::Num>) -> Self {
+        Self::new(nodes, rect, Query::In)
+    }
+
+    ///
+    /// Count the elements that intersect the rect.
+    /// The nodes are those of a whole tree in dfs preorder, see [`Tree::get_nodes()`]
+    ///
+    pub fn intersect_rect(nodes: &'b [Node<'a, T, T::Num>], rect: Rect<T::Num>) -> Self {
+        Self::new(nodes, rect, Query::Intersect(T::INTERSECTION_MODE))
+    }
+
+    fn new(nodes: &'b [Node<'a, T, T::Num>], rect: Rect<T::Num>, query: Query) -> Self {
+        let unbounded = Range {
+            start: None,
+            end: None,
+        };
+        RectCounter {
+            nodes,
+            rect,
+            query,
+            region: Rect {
+                x: unbounded,
+                y: unbounded,
+            },
+        }
+    }
+
+    ///The [`Node::min_elem`] of the root.
+    pub fn num_elem(&self) -> usize {
+        self.nodes.first().map_or(0, |n| n.min_elem)
+    }
+
+    ///
+    /// Count the elements of the root, or of the whole subtree if it is known
+    /// to be in the rect. What is left are the two subtrees below the root.
+    ///
+    pub fn count_and_next(self) -> (usize, Option<[Self; 2]>) {
+        let RectCounter {
+            nodes,
+            rect,
+            query,
+            region,
+        } = self;
+
+        let (nn, rest) = match nodes.split_first() {
+            Some(a) => a,
+            None => return (0, None),
+        };
+
+        let (inside, outside) =
+            [AxisDyn::X, AxisDyn::Y]
+                .into_iter()
+                .fold((true, false), |(inside, outside), axis| {
+                    let (r, b) = (rect.get_range(axis), region.get_range(axis));
+                    (
+                        inside
+                            && matches!(b.start, Some(s) if r.start <= s)
+                            && matches!(b.end, Some(e) if e <= r.end),
+                        outside
+                            || matches!(b.start, Some(s) if r.end <= s)
+                            || matches!(b.end, Some(e) if e <= r.start),
+                    )
+                });
+
+        if outside {
+            return (0, None);
+        }
+        if inside {
+            return (nodes.iter().map(|n| n.range.len()).sum(), None);
+        }
+
+        let axis = nn.axis;
+        let div = match (nn.div, rest.is_empty()) {
+            (Some(div), false) => Some(div),
+            (None, false) => return (0, None),
+            (_, true) => None,
+        };
+
+        let num = if nn.range.is_empty() {
+            0
+        } else if node_inside(&rect, &region, axis, &nn.cont, query) {
+            nn.range.len()
+        } else {
+            get_section(
+                axis.next(),
+                &nn.range,
+                rect.get_range(axis.next()),
+                query.mode(),
+            )
+            .iter()
+            .filter(|a| match query {
+                Query::In => rect.contains_rect(a.get()),
+                Query::Intersect(mode) => mode.intersects_rect(&rect, a.get()),
+            })
+            .count()
+        };
+
+        let rest = div.map(|div| {
+            let (left, right) = rest.split_at(rest.len() / 2);
+
+            let mut left_region = region;
+            left_region.get_range_mut(axis).end = Some(div);
+            let mut right_region = region;
+            right_region.get_range_mut(axis).start = Some(div);
+
+            [
+                RectCounter {
+                    nodes: left,
+                    rect,
+                    query,
+                    region: left_region,
+                },
+                RectCounter {
+                    nodes: right,
+                    rect,
+                    query,
+                    region: right_region,
+                },
+            ]
+        });
+
+        (num, rest)
+    }
+
+    pub fn count_seq(self) -> usize {
+        let (num, rest) = self.count_and_next();
+        num + rest.map_or(0, |[left, right]| left.count_seq() + right.count_seq())
+    }
+}
+
+//The elements of a node are within its container range along its axis,
+//and strictly within the dividers above it along the other axis.
+#[inline(always)]
+fn node_inside<N: Num>(
+    rect: &Rect<N>,
+    region: &Rect<Option<N>>,
+    axis: AxisDyn,
+    cont: &Range<N>,
+    query: Query,
+) -> bool {
+    let (r, b) = (rect.get_range(axis.next()), region.get_range(axis.next()));
+    let other_inside =
+        matches!(b.start, Some(s) if r.start <= s) && matches!(b.end, Some(e) if e <= r.end);
+
+    //Elements with no size on the border of the rect do not intersect it
+    //if they are half open, so the container range has to be strictly inside.
+    let mode = query.mode();
+    let r = rect.get_range(axis);
+    other_inside && !mode.is_before(cont.start, r.start) && !mode.is_before(r.end, cont.end)
+}
+
+///
+/// Counts the elements of a subtree that are within the radius of a point.
+/// Subtrees are pruned the same way as with [`Tree::find_all_in_radius()`].
+///
+pub struct RadiusCounter<'a, 'b, T: Aabb> {
+    vistr: VistrMutPin<'a, Node<'b, T, T::Num>>,
+    point: Vec2<T::Num>,
+    radius: T::Num,
+}
+
+impl<'a, 'b, T: Aabb> RadiusCounter<'a, 'b, T> {
+    pub fn new(
+        vistr: VistrMutPin<'a, Node<'b, T, T::Num>>,
+        point: Vec2<T::Num>,
+        radius: T::Num,
+    ) -> Self {
+        RadiusCounter {
+            vistr,
+            point,
+            radius,
+        }
+    }
+
+    ///The [`Node::min_elem`] of the root.
+    pub fn num_elem(&self) -> usize {
+        self.vistr.borrow().next().0.min_elem
+    }
+
+    ///
+    /// Count the elements of the root. What is left are the subtrees
+    /// below the root that may have elements within the radius.
+    ///
+    pub fn count_and_next(self, knear: &mut impl Knearest<T>) -> (usize, [Option<Self>; 2]) {
+        let RadiusCounter {
+            vistr,
+            point,
+            radius,
+        } = self;
+
+        let (nn, rest) = vistr.next();
+        let (handle_node, handle) = reach(&nn, rest.is_none(), point, radius, knear);
+
+        let rest = match rest {
+            Some([left, right]) => {
+                let [l, r] = handle;
+                [
+                    l.then(|| RadiusCounter::new(left, point, radius)),
+                    r.then(|| RadiusCounter::new(right, point, radius)),
+                ]
+            }
+            None => [None, None],
+        };
+
+        let mut num = 0;
+        if handle_node {
+            for b in nn.into_range().iter_mut() {
+                consider(point, radius, knear, b, &mut |_, _| num += 1);
+            }
+        }
+
+        (num, rest)
+    }
+
+    pub fn count_seq(self, knear: &mut impl Knearest<T>) -> usize {
+        let (num, rest) = self.count_and_next(knear);
+        rest.into_iter()
+            .flatten()
+            .fold(num, |num, a| num + a.count_seq(knear))
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_count_rect(&mut self, mut rect: Rect<T::Num>) {
+            let tree = Tree::new(self.inner);
+            let (num_in, num_intersect) =
+                (tree.count_in_rect(&rect), tree.count_intersect_rect(&rect));
+
+            let (mut naive_in, mut naive_intersect) = (0, 0);
+            let mut naive = Naive::new(self.inner);
+            naive.find_all_in_rect(AabbPin::new(&mut rect), |_, _| naive_in += 1);
+            naive.find_all_intersect_rect(AabbPin::new(&mut rect), |_, _| naive_intersect += 1);
+
+            assert_eq!(num_in, naive_in);
+            assert_eq!(num_intersect, naive_intersect);
+        }
+
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_count_radius(
+            &mut self,
+            point: Vec2<T::Num>,
+            radius: T::Num,
+            mut knear: impl Knearest<T>,
+        ) {
+            let num = Tree::new(self.inner).count_in_radius(point, radius, &mut knear);
+
+            let mut naive = 0;
+            Naive::new(self.inner).find_all_in_radius(point, radius, knear, |_, _| naive += 1);
+
+            assert_eq!(num, naive);
+        }
+    }
+}
@@ -44,36 +44,48 @@ impl<'a, T: Aabb> Tree<'a, T> {
 
         ///Calls the user supplied function on each divider.
         ///Since the leaves do not have dividers, it is not called for the leaves.
-        fn draw<A: Axis, T: Aabb, D: DividerDrawer<T>>(
-            axis: A,
+        fn draw<T: Aabb, D: DividerDrawer<T>>(
             vistr: Vistr<Node<T, T::Num>>,
             dr: &mut D,
             rect: Rect<T::Num>,
         ) {
-            fn recc<A: Axis, T: Aabb, D: DividerDrawer<T>>(
-                axis: A,
+            fn recc<T: Aabb, D: DividerDrawer<T>>(
                 stuff: LevelIter<Vistr<Node<T, T::Num>>>,
                 dr: &mut D,
                 rect: Rect<T::Num>,
             ) {
                 let ((depth, nn), rest) = stuff.next();
+                match nn.axis {
+                    AxisDyn::X => recc_axis(XAXIS, nn, rest, dr, rect, depth),
+                    AxisDyn::Y => recc_axis(YAXIS, nn, rest, dr, rect, depth),
+                }
+            }
+
+            fn recc_axis<A: Axis, T: Aabb, D: DividerDrawer<T>>(
+                axis: A,
+                nn: &Node<T, T::Num>,
+                rest: LevelRest<Vistr<Node<T, T::Num>>>,
+                dr: &mut D,
+                rect: Rect<T::Num>,
+                depth: Depth,
+            ) {
                 dr.draw_divider(axis, nn, &rect, depth.0);
 
                 if let Some([left, right]) = rest {
                     if let Some(div) = nn.div {
                         let (a, b) = rect.subdivide(axis, div);
 
-                        recc(axis.next(), left, dr, a);
-                        recc(axis.next(), right, dr, b);
+                        recc(left, dr, a);
+                        recc(right, dr, b);
                     }
                 }
             }
 
-            recc(axis, vistr.with_depth(Depth(0)), dr, rect);
+            recc(vistr.with_depth(Depth(0)), dr, rect);
         }
 
         let mut d = DrawClosure { line };
 
-        draw(default_axis(), tree.vistr(), &mut d, rect)
+        draw(tree.vistr(), &mut d, rect)
     }
 }
@@ -1,43 +1,411 @@
 //!
 //! Find colliding pairs between two independent sets
 //!
+//! ```rust
+//! use broccoli::rect;
+//!
+//! let mut players = [rect(0, 10, 0, 10), rect(50, 60, 50, 60)];
+//! let mut walls = [rect(5, 15, 5, 15), rect(20, 30, 20, 30), rect(8, 9, 8, 9)];
+//!
+//! let mut a = broccoli::Tree::new(&mut players);
+//! let mut b = broccoli::Tree::new(&mut walls);
+//!
+//! let mut num = 0;
+//! a.find_colliding_pairs_with(&mut b, |_, _| num += 1);
+//! assert_eq!(num, 2);
+//! ```
+//!
 
 use super::*;
+use tools::get_section_mut;
+
+///
+/// The bounding rect of the elements of a node, and of the elements
+/// of the node and every node below it. None if there are no elements.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct NodeBounds<N> {
+    pub node: Option<Rect<N>>,
+    pub subtree: Option<Rect<N>>,
+}
 
 impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// The [`NodeBounds`] of every node in dfs preorder.
+    ///
+    #[must_use]
+    pub fn node_bounds(&self) -> Vec<NodeBounds<T::Num>> {
+        let mut out = Vec::with_capacity(self.num_nodes());
+        bounds(self.vistr(), &mut out);
+        out
+    }
+
+    ///
+    /// Find all intersecting pairs between the elements of this tree and the elements of another tree.
+    /// No intersecting pairs within each tree are looked for, only those between the two.
+    ///
+    /// Both trees are recursed at the same time, so that subtrees of one tree are only
+    /// checked against subtrees of the other tree whose bounding rects they intersect.
+    ///
     pub fn find_colliding_pairs_with<X: Aabb<Num = T::Num>>(
         &mut self,
         other: &mut crate::Tree<X>,
-        func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut X>),
+        mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut X>),
     ) {
-        let i = other
-            .get_nodes_mut()
-            .iter_mut()
-            .flat_map(|x| x.into_range().iter_mut());
-        self.find_colliding_pairs_with_iter(i, func);
+        let a_bounds = self.node_bounds();
+        let b_bounds = other.node_bounds();
+
+        DualVisitor::new(self.vistr_mut(), &a_bounds, other.vistr_mut(), &b_bounds)
+            .recurse_seq(&mut func);
     }
 
+    ///
+    /// Find all intersecting pairs between the elements in this tree, and the specified elements.
+    /// Every specified element is queried against the tree on its own, so prefer
+    /// [`Tree::find_colliding_pairs_with()`] unless there are only a few of them.
+    ///
     pub fn find_colliding_pairs_with_iter<'x, X: Aabb<Num = T::Num> + 'x>(
         &mut self,
         other: impl Iterator<Item = AabbPin<&'x mut X>>,
         mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut X>),
     ) {
-        //TODO instead of create just a list of BBox, construct a tree using the dividers of the current tree.
-        //This way we can parallelize this function.
-        //Find all intersecting pairs between the elements in this tree, and the specified elements.
-        //No intersecting pairs within each group are looked for, only those between the two groups.
-        //For best performance the group that this tree is built around should be the bigger of the two groups.
-        //Since the dividers of the tree are used to divide and conquer the problem.
-        //If the other group is bigger, consider building the DinoTree around that group instead, and
-        //leave this group has a list of bots.
-        //
-        //Currently this is implemented naively using for_all_intersect_rect_mut().
-        //But using the api, it is possible to build up a tree using the current trees dividers
-        //to exploit the divide and conquer properties of this problem.
-        //The two trees could be recursed at the same time to break up the problem.
-
         for i in other {
-            self.find_all_in_rect(i, |r, a| func(a, r))
+            self.find_all_intersect_rect(i, |r, a| func(a, r))
+        }
+    }
+}
+
+//Push the bounds of each node in dfs preorder.
+//Returns the bounds of the subtree.
+fn bounds<T: Aabb>(
+    v: Vistr<Node<T, T::Num>>,
+    out: &mut Vec<NodeBounds<T::Num>>,
+) -> Option<Rect<T::Num>> {
+    fn union<N: Num>(a: Option<Rect<N>>, b: Option<Rect<N>>) -> Option<Rect<N>> {
+        match (a, b) {
+            (Some(mut a), Some(b)) => {
+                a.grow_to_fit(&b);
+                Some(a)
+            }
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+
+    let (nn, rest) = v.next();
+
+    let node = nn
+        .range
+        .iter()
+        .fold(None, |acc, a| union(acc, Some(*a.get())));
+
+    let index = out.len();
+    out.push(NodeBounds {
+        node,
+        subtree: node,
+    });
+
+    if let Some([left, right]) = rest {
+        let left = bounds(left, out);
+        let right = bounds(right, out);
+        out[index].subtree = union(node, union(left, right));
+    }
+    out[index].subtree
+}
+
+#[inline(always)]
+fn intersects<N: Num>(a: &Option<Rect<N>>, b: &Option<Rect<N>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.intersects_rect(b),
+        _ => false,
+    }
+}
+
+///
+/// Visits a subtree of one tree and a subtree of another tree at the same time.
+///
+pub struct DualVisitor<'a, 'x, 'b, T: Aabb, X: Aabb<Num = T::Num>> {
+    a: VistrMutPin<'b, Node<'a, T, T::Num>>,
+    a_bounds: &'b [NodeBounds<T::Num>],
+    b: VistrMutPin<'b, Node<'x, X, T::Num>>,
+    b_bounds: &'b [NodeBounds<T::Num>],
+}
+
+impl<'a, 'x, 'b, T: Aabb, X: Aabb<Num = T::Num>> DualVisitor<'a, 'x, 'b, T, X> {
+    ///
+    /// The bounds are those returned by [`Tree::node_bounds()`] of each tree.
+    ///
+    pub fn new(
+        a: VistrMutPin<'b, Node<'a, T, T::Num>>,
+        a_bounds: &'b [NodeBounds<T::Num>],
+        b: VistrMutPin<'b, Node<'x, X, T::Num>>,
+        b_bounds: &'b [NodeBounds<T::Num>],
+    ) -> Self {
+        assert_eq!(a_bounds.len(), a.borrow().into_slice().len());
+        assert_eq!(b_bounds.len(), b.borrow().into_slice().len());
+        DualVisitor {
+            a,
+            a_bounds,
+            b,
+            b_bounds,
+        }
+    }
+
+    ///The smaller of the [`Node::min_elem`] of the two roots.
+    pub fn num_elem(&self) -> usize {
+        let (a, _) = self.a.borrow().next();
+        let (b, _) = self.b.borrow().next();
+        a.min_elem.min(b.min_elem)
+    }
+
+    ///
+    /// Handle every pair that involves the root of either subtree.
+    /// What is left are the pairs between the children of both roots.
+    ///
+    pub fn collide_and_next<F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>)>(
+        self,
+        func: &mut F,
+    ) -> Option<DualChildren<'a, 'x, 'b, T, X>> {
+        let (a_bounds, a_rest_bounds) = self.a_bounds.split_first().unwrap();
+        let (b_bounds, b_rest_bounds) = self.b_bounds.split_first().unwrap();
+
+        if !intersects(&a_bounds.subtree, &b_bounds.subtree) {
+            return None;
+        }
+
+        let (a_node, mut a_rest) = self.a.next();
+        let (b_node, mut b_rest) = self.b.next();
+
+        let mut a_root = NodeElems {
+            axis: a_node.axis,
+            rect: a_bounds.node,
+            range: a_node.into_range(),
+        };
+        let mut b_root = NodeElems {
+            axis: b_node.axis,
+            rect: b_bounds.node,
+            range: b_node.into_range(),
+        };
+
+        let (a_left_bounds, a_right_bounds) = a_rest_bounds.split_at(a_rest_bounds.len() / 2);
+        let (b_left_bounds, b_right_bounds) = b_rest_bounds.split_at(b_rest_bounds.len() / 2);
+
+        //The root of a against the root of b and every node below it.
+        collide_nodes(a_root.borrow_mut(), b_root.borrow_mut(), func);
+        if let Some([left, right]) = &mut b_rest {
+            descend(a_root.borrow_mut(), left.borrow_mut(), b_left_bounds, func);
+            descend(a_root, right.borrow_mut(), b_right_bounds, func);
+        }
+
+        //The root of b against every node below the root of a.
+        if let Some([left, right]) = &mut a_rest {
+            let func = &mut |b: AabbPin<&mut X>, a: AabbPin<&mut T>| func(a, b);
+            descend(b_root.borrow_mut(), left.borrow_mut(), a_left_bounds, func);
+            descend(b_root, right.borrow_mut(), a_right_bounds, func);
+        }
+
+        match (a_rest, b_rest) {
+            (Some(a), Some(b)) => Some(DualChildren {
+                a,
+                a_bounds: [a_left_bounds, a_right_bounds],
+                b,
+                b_bounds: [b_left_bounds, b_right_bounds],
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn recurse_seq<F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>)>(self, func: &mut F) {
+        if let Some(mut rest) = self.collide_and_next(func) {
+            for crossed in [false, true] {
+                let [a, b] = rest.pairs(crossed);
+                a.recurse_seq(func);
+                b.recurse_seq(func);
+            }
+        }
+    }
+}
+
+///
+/// The children of the roots of both subtrees of a [`DualVisitor`].
+///
+pub struct DualChildren<'a, 'x, 'b, T: Aabb, X: Aabb<Num = T::Num>> {
+    a: [VistrMutPin<'b, Node<'a, T, T::Num>>; 2],
+    a_bounds: [&'b [NodeBounds<T::Num>]; 2],
+    b: [VistrMutPin<'b, Node<'x, X, T::Num>>; 2],
+    b_bounds: [&'b [NodeBounds<T::Num>]; 2],
+}
+
+impl<'a, 'x, 'b, T: Aabb, X: Aabb<Num = T::Num>> DualChildren<'a, 'x, 'b, T, X> {
+    ///
+    /// Pair up the children of one root with the children of the other.
+    /// Left is paired with left and right with right, or if `crossed` is true,
+    /// left with right and right with left. Together the two pairings cover
+    /// every pair of children, and the two visitors of one pairing
+    /// can be recursed in parallel.
+    ///
+    pub fn pairs(&mut self, crossed: bool) -> [DualVisitor<'a, 'x, '_, T, X>; 2] {
+        let [a_left, a_right] = &mut self.a;
+        let [b0, b1] = &mut self.b;
+
+        let ((b_left, b_left_bounds), (b_right, b_right_bounds)) = if crossed {
+            ((b1, self.b_bounds[1]), (b0, self.b_bounds[0]))
+        } else {
+            ((b0, self.b_bounds[0]), (b1, self.b_bounds[1]))
+        };
+
+        [
+            DualVisitor {
+                a: a_left.borrow_mut(),
+                a_bounds: self.a_bounds[0],
+                b: b_left.borrow_mut(),
+                b_bounds: b_left_bounds,
+            },
+            DualVisitor {
+                a: a_right.borrow_mut(),
+                a_bounds: self.a_bounds[1],
+                b: b_right.borrow_mut(),
+                b_bounds: b_right_bounds,
+            },
+        ]
+    }
+}
+
+//The elements of a node along with the axis of the node and their bounding rect.
+struct NodeElems<'a, T: Aabb> {
+    axis: AxisDyn,
+    rect: Option<Rect<T::Num>>,
+    range: AabbPin<&'a mut [T]>,
+}
+
+impl<'a, T: Aabb> NodeElems<'a, T> {
+    fn borrow_mut(&mut self) -> NodeElems<'_, T> {
+        NodeElems {
+            axis: self.axis,
+            rect: self.rect,
+            range: self.range.borrow_mut(),
+        }
+    }
+}
+
+//Check the anchor against every node of the subtree that it could intersect.
+fn descend<T: Aabb, X: Aabb<Num = T::Num>, F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>)>(
+    mut anchor: NodeElems<T>,
+    v: VistrMutPin<Node<X, T::Num>>,
+    bounds: &[NodeBounds<T::Num>],
+    func: &mut F,
+) {
+    let (nn_bounds, rest_bounds) = bounds.split_first().unwrap();
+    if !intersects(&anchor.rect, &nn_bounds.subtree) {
+        return;
+    }
+
+    let (nn, rest) = v.next();
+    let nn = NodeElems {
+        axis: nn.axis,
+        rect: nn_bounds.node,
+        range: nn.into_range(),
+    };
+    collide_nodes(anchor.borrow_mut(), nn, func);
+
+    if let Some([left, right]) = rest {
+        let (left_bounds, right_bounds) = rest_bounds.split_at(rest_bounds.len() / 2);
+        descend(anchor.borrow_mut(), left, left_bounds, func);
+        descend(anchor, right, right_bounds, func);
+    }
+}
+
+fn collide_nodes<T: Aabb, X: Aabb<Num = T::Num>, F: FnMut(AabbPin<&mut T>, AabbPin<&mut X>)>(
+    a: NodeElems<T>,
+    b: NodeElems<X>,
+    func: &mut F,
+) {
+    let (a_rect, b_rect) = match (&a.rect, &b.rect) {
+        (Some(a), Some(b)) if a.intersects_rect(b) => (a, b),
+        _ => return,
+    };
+
+    //Only the elements that are within the other node along the axis they are sorted along.
+    let (a_axis, b_axis) = (a.axis.next(), b.axis.next());
+    let a_range = get_section_mut(a_axis, a.range, b_rect.get_range(a_axis));
+    let mut b_range = get_section_mut(b_axis, b.range, a_rect.get_range(b_axis));
+
+    let mode = T::INTERSECTION_MODE.stricter(X::INTERSECTION_MODE);
+    let same_axis = a_axis.is_equal_to(b_axis);
+    for mut x in a_range {
+        for y in b_range.borrow_mut() {
+            //Exploit the sorted property, to exit early
+            if same_axis && mode.is_before(x.range(a_axis).end, y.range(a_axis).start) {
+                break;
+            }
+            if mode.intersects_rect(x.get(), y.get()) {
+                func(x.borrow_mut(), y);
+            }
+        }
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb> Naive<'a, T> {
+        pub fn find_colliding_pairs_with<X: Aabb<Num = T::Num>>(
+            &mut self,
+            other: &mut Naive<X>,
+            mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut X>),
+        ) {
+            let mode = T::INTERSECTION_MODE.stricter(X::INTERSECTION_MODE);
+            for mut a in self.iter_mut() {
+                for b in other.iter_mut() {
+                    if mode.intersects_rect(a.get(), b.get()) {
+                        func(a.borrow_mut(), b);
+                    }
+                }
+            }
+        }
+    }
+
+    impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_colliding_pairs_with<X: Aabb<Num = T::Num> + ManySwap>(
+            &mut self,
+            other: &mut [X],
+        ) {
+            self.assert_colliding_pairs_with_mode(other);
+
+            //The same rects in each intersection mode, and one of each.
+            let mut closed: Vec<_> = self.inner.iter().map(|a| *a.get()).collect();
+            let mut other_closed: Vec<_> = other.iter().map(|a| *a.get()).collect();
+            Assert::new(&mut closed).assert_colliding_pairs_with_mode(&mut other_closed);
+
+            let mut half_open: Vec<_> = closed.iter().copied().map(HalfOpen).collect();
+            let mut other_half_open: Vec<_> = other_closed.iter().copied().map(HalfOpen).collect();
+            Assert::new(&mut half_open).assert_colliding_pairs_with_mode(&mut other_half_open);
+
+            Assert::new(&mut closed).assert_colliding_pairs_with_mode(&mut other_half_open);
+        }
+
+        fn assert_colliding_pairs_with_mode<X: Aabb<Num = T::Num> + ManySwap>(
+            &mut self,
+            other: &mut [X],
+        ) {
+            use crate::assert::into_ptr_usize;
+
+            let mut res_dino = Vec::new();
+            Tree::new(self.inner).find_colliding_pairs_with(&mut Tree::new(other), |a, b| {
+                res_dino.push((into_ptr_usize(a), into_ptr_usize(b)));
+            });
+
+            let mut res_naive = Vec::new();
+            Naive::new(self.inner).find_colliding_pairs_with(&mut Naive::new(other), |a, b| {
+                res_naive.push((into_ptr_usize(a), into_ptr_usize(b)));
+            });
+
+            res_naive.sort_unstable();
+            res_dino.sort_unstable();
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
         }
     }
 }
@@ -37,13 +37,9 @@ impl<'a, T: Aabb> Tree<'a, T> {
             closest,
         };
 
-        rec.recc(default_axis(), dt);
+        rec.recc(dt);
 
-        let num_entries = rec.closest.curr_num;
-        KResult {
-            num_entries,
-            inner: rec.closest.into_sorted(),
-        }
+        rec.closest.into_result()
     }
 
     pub fn find_knearest_closure(
@@ -68,6 +64,7 @@ impl<'a, T: Aabb> Tree<'a, T> {
 ///
 /// Find nearest using just axis alined bounding boxes. No fine-grained.
 ///
+#[derive(Copy, Clone)]
 pub struct AabbKnearest;
 
 impl<T: Aabb> Knearest<T> for AabbKnearest
@@ -167,6 +164,139 @@ impl<T: Aabb, K: Knearest<T>> Knearest<T> for &mut K {
     }
 }
 
+///
+/// Like [`Knearest`] except it is only given shared references to the elements.
+/// See [`TreeRef::find_knearest()`]
+///
+pub trait KnearestRef<T: Aabb> {
+    fn distance_to_aaline<A: Axis>(&mut self, point: Vec2<T::Num>, axis: A, val: T::Num) -> T::Num;
+
+    fn distance_to_broad(&mut self, point: Vec2<T::Num>, a: &T) -> Option<T::Num>;
+
+    fn distance_to_fine(&mut self, point: Vec2<T::Num>, a: &T) -> T::Num;
+}
+
+impl<T: Aabb> KnearestRef<T> for AabbKnearest
+where
+    T::Num: num_traits::Signed + num_traits::Zero,
+{
+    fn distance_to_aaline<A: Axis>(&mut self, point: Vec2<T::Num>, axis: A, a: T::Num) -> T::Num {
+        Knearest::<T>::distance_to_aaline(self, point, axis, a)
+    }
+
+    fn distance_to_broad(&mut self, _point: Vec2<T::Num>, _a: &T) -> Option<T::Num> {
+        None
+    }
+
+    fn distance_to_fine(&mut self, point: Vec2<T::Num>, a: &T) -> T::Num {
+        use num_traits::Zero;
+
+        a.get()
+            .distance_squared_to_point(point)
+            .unwrap_or_else(T::Num::zero)
+    }
+}
+
+impl<T: Aabb, K: KnearestRef<T>> KnearestRef<T> for &mut K {
+    fn distance_to_aaline<A: Axis>(&mut self, point: Vec2<T::Num>, axis: A, val: T::Num) -> T::Num {
+        (*self).distance_to_aaline(point, axis, val)
+    }
+
+    fn distance_to_broad(&mut self, point: Vec2<T::Num>, a: &T) -> Option<T::Num> {
+        (*self).distance_to_broad(point, a)
+    }
+
+    fn distance_to_fine(&mut self, point: Vec2<T::Num>, a: &T) -> T::Num {
+        (*self).distance_to_fine(point, a)
+    }
+}
+
+impl<'a, 'b, T: Aabb> TreeRef<'a, 'b, T> {
+    ///
+    /// Like [`Tree::find_knearest()`] except shared references are returned, closest first.
+    /// All the elements tied at a distance are returned, so there may be more than `num`.
+    ///
+    pub fn find_knearest(
+        &self,
+        point: Vec2<T::Num>,
+        num: usize,
+        mut ktrait: impl KnearestRef<T>,
+    ) -> Vec<(&'b T, T::Num)> {
+        let mut closest = ClosestCand::new(num);
+        recc_ref(self.vistr(), point, &mut ktrait, &mut closest);
+        closest.into_sorted()
+    }
+}
+
+//Same as Recurser except with shared references.
+fn recc_ref<'b, T: Aabb, K: KnearestRef<T>>(
+    m: Vistr<'b, Node<T, T::Num>>,
+    point: Vec2<T::Num>,
+    knear: &mut K,
+    closest: &mut ClosestCand<&'b T, T::Num>,
+) {
+    fn should_recurse<T: Aabb, K: KnearestRef<T>, A: Axis>(
+        point: Vec2<T::Num>,
+        knear: &mut K,
+        closest: &ClosestCand<&T, T::Num>,
+        line: (A, T::Num),
+    ) -> bool {
+        if let Some(m) = closest.full_and_max_distance() {
+            knear.distance_to_aaline(point, line.0, line.1) < m
+        } else {
+            true
+        }
+    }
+
+    let (nn, rest) = m.next();
+    let axis = nn.axis;
+
+    let handle_node = match rest {
+        Some([left, right]) => {
+            let div = match nn.div {
+                Some(b) => b,
+                None => return,
+            };
+
+            let line = (axis, div);
+
+            //recurse first. more likely closest is in a child.
+            if *point.get_axis(axis) < div {
+                recc_ref(left, point, knear, closest);
+                if should_recurse(point, knear, closest, line) {
+                    recc_ref(right, point, knear, closest);
+                }
+            } else {
+                recc_ref(right, point, knear, closest);
+                if should_recurse(point, knear, closest, line) {
+                    recc_ref(left, point, knear, closest);
+                }
+            }
+
+            if !nn.range.is_empty() {
+                match nn.cont.contains_ext(*point.get_axis(axis)) {
+                    core::cmp::Ordering::Less => {
+                        should_recurse(point, knear, closest, (axis, nn.cont.start))
+                    }
+                    core::cmp::Ordering::Greater => {
+                        should_recurse(point, knear, closest, (axis, nn.cont.end))
+                    }
+                    core::cmp::Ordering::Equal => true,
+                }
+            } else {
+                false
+            }
+        }
+        None => true,
+    };
+
+    if handle_node {
+        for bot in nn.range.iter() {
+            closest.consider_ref(&point, knear, bot);
+        }
+    }
+}
+
 /// Returned by k_nearest_mut
 #[derive(Debug)]
 pub struct KnearestResult<'a, T: Aabb> {
@@ -174,20 +304,62 @@ pub struct KnearestResult<'a, T: Aabb> {
     pub mag: T::Num,
 }
 
-struct ClosestCand<'a, T: Aabb> {
+//Generic over the bot so that it can hold shared references as well.
+struct ClosestCand<B, N> {
     //Can have multiple bots with the same mag. So the length could be bigger than num.
-    bots: Vec<KnearestResult<'a, T>>,
+    bots: Vec<(B, N)>,
     //The current number of different distances in the vec
     curr_num: usize,
     //The max number of different distances.
     num: usize,
 }
-impl<'a, T: Aabb> ClosestCand<'a, T> {
+
+impl<'a, T: Aabb> ClosestCand<AabbPin<&'a mut T>, T::Num> {
+    fn consider<K: Knearest<T>>(
+        &mut self,
+        point: &Vec2<T::Num>,
+        knear: &mut K,
+        mut curr_bot: AabbPin<&'a mut T>,
+    ) {
+        if let Some(long_dis) = knear.distance_to_broad(*point, curr_bot.borrow_mut()) {
+            if self.is_too_far(long_dis) {
+                return;
+            }
+        }
+        let curr_dis = knear.distance_to_fine(*point, curr_bot.borrow_mut());
+        self.insert(curr_bot, curr_dis);
+    }
+
+    fn into_result(self) -> KResult<'a, T> {
+        KResult {
+            num_entries: self.curr_num,
+            inner: self
+                .bots
+                .into_iter()
+                .map(|(bot, mag)| KnearestResult { bot, mag })
+                .collect(),
+        }
+    }
+}
+
+impl<'b, T: Aabb> ClosestCand<&'b T, T::Num> {
+    fn consider_ref<K: KnearestRef<T>>(&mut self, point: &Vec2<T::Num>, knear: &mut K, bot: &'b T) {
+        if let Some(long_dis) = knear.distance_to_broad(*point, bot) {
+            if self.is_too_far(long_dis) {
+                return;
+            }
+        }
+        let curr_dis = knear.distance_to_fine(*point, bot);
+        self.insert(bot, curr_dis);
+    }
+}
+
+impl<B, N: PartialOrd + Copy> ClosestCand<B, N> {
     //First is the closest
-    fn into_sorted(self) -> Vec<KnearestResult<'a, T>> {
+    fn into_sorted(self) -> Vec<(B, N)> {
         self.bots
     }
-    fn new(num: usize) -> ClosestCand<'a, T> {
+    fn new(num: usize) -> ClosestCand<B, N> {
         let bots = Vec::with_capacity(num);
         ClosestCand {
             bots,
@@ -196,23 +368,16 @@ impl<'a, T: Aabb> ClosestCand<'a, T> {
         }
     }
 
-    fn consider<K: Knearest<T>>(
-        &mut self,
-        point: &Vec2<T::Num>,
-        knear: &mut K,
-        mut curr_bot: AabbPin<&'a mut T>,
-    ) {
-        if let Some(long_dis) = knear.distance_to_broad(*point, curr_bot.borrow_mut()) {
-            if self.curr_num == self.num {
-                if let Some(l) = self.bots.last() {
-                    if long_dis > l.mag {
-                        return;
-                    }
-                }
+    fn is_too_far(&self, long_dis: N) -> bool {
+        if self.curr_num == self.num {
+            if let Some(l) = self.bots.last() {
+                return long_dis > l.1;
             }
         }
-        let curr_dis = knear.distance_to_fine(*point, curr_bot.borrow_mut());
+        false
+    }
 
+    fn insert(&mut self, curr_bot: B, curr_dis: N) {
         let arr = &mut self.bots;
 
         let mut insert_index = None;
@@ -220,14 +385,14 @@ impl<'a, T: Aabb> ClosestCand<'a, T> {
         //The closest bots are at the start.
 
         for (i, a) in arr.iter().enumerate() {
-            if curr_dis < a.mag {
+            if curr_dis < a.1 {
                 //If we find a bot closer than everything we've had before,
                 //start a new group.
 
                 insert_index = Some(i);
                 self.curr_num += 1;
                 break;
-            } else if curr_dis == a.mag {
+            } else if curr_dis == a.1 {
                 //If we find a bot at the same distance of another bot, add it to that group.
                 insert_index = Some(i);
                 break;
@@ -235,21 +400,15 @@ impl<'a, T: Aabb> ClosestCand<'a, T> {
         }
 
         if let Some(i) = insert_index {
-            arr.insert(
-                i,
-                KnearestResult {
-                    bot: curr_bot,
-                    mag: curr_dis,
-                },
-            );
+            arr.insert(i, (curr_bot, curr_dis));
 
             //If we have too many groups, delete the group thats furthest away.
             if self.curr_num > self.num {
                 //We know its not empty if we have gotten here
-                let last_mag = arr.last().unwrap().mag;
+                let last_mag = arr.last().unwrap().1;
                 self.curr_num -= 1;
                 while let Some(k) = arr.last() {
-                    if k.mag == last_mag {
+                    if k.1 == last_mag {
                         arr.pop();
                     } else {
                         break;
@@ -261,24 +420,18 @@ impl<'a, T: Aabb> ClosestCand<'a, T> {
             //make a new group at the end.
             if self.curr_num < self.num {
                 self.curr_num += 1;
-                arr.insert(
-                    arr.len(),
-                    KnearestResult {
-                        bot: curr_bot,
-                        mag: curr_dis,
-                    },
-                );
+                arr.push((curr_bot, curr_dis));
             }
         }
     }
 
-    fn full_and_max_distance(&self) -> Option<T::Num> {
+    fn full_and_max_distance(&self) -> Option<N> {
         assert!(crate::queries::is_sorted_by(&self.bots, |a, b| a
-            .mag
-            .partial_cmp(&b.mag)));
+            .1
+            .partial_cmp(&b.1)));
 
         if self.curr_num == self.num {
-            self.bots.last().map(|a| a.mag)
+            self.bots.last().map(|a| a.1)
         } else {
             None
         }
@@ -288,7 +441,7 @@ impl<'a, T: Aabb> ClosestCand<'a, T> {
 struct Recurser<'a, T: Aabb, K: Knearest<T>> {
     knear: K,
     point: Vec2<T::Num>,
-    closest: ClosestCand<'a, T>,
+    closest: ClosestCand<AabbPin<&'a mut T>, T::Num>,
 }
 
 impl<'a, T: Aabb, K: Knearest<T>> Recurser<'a, T, K> {
@@ -301,12 +454,20 @@ impl<'a, T: Aabb, K: Knearest<T>> Recurser<'a, T, K> {
         }
     }
 
-    fn recc<'b: 'a, A: Axis>(
+    fn recc<'b: 'a>(&mut self, stuff: LevelIter<VistrMutPin<'a, Node<'b, T, T::Num>>>) {
+        let ((_depth, nn), rest) = stuff.next();
+        match nn.axis {
+            AxisDyn::X => self.recc_axis(XAXIS, nn, rest),
+            AxisDyn::Y => self.recc_axis(YAXIS, nn, rest),
+        }
+    }
+
+    fn recc_axis<'b: 'a, A: Axis>(
         &mut self,
         axis: A,
-        stuff: LevelIter<VistrMutPin<'a, Node<'b, T, T::Num>>>,
+        nn: AabbPin<&'a mut Node<'b, T, T::Num>>,
+        rest: LevelRest<VistrMutPin<'a, Node<'b, T, T::Num>>>,
     ) {
-        let ((_depth, nn), rest) = stuff.next();
         //let nn = nn.get_mut();
         let handle_node = match rest {
             Some([left, right]) => {
@@ -319,14 +480,14 @@ impl<'a, T: Aabb, K: Knearest<T>> Recurser<'a, T, K> {
 
                 //recurse first. more likely closest is in a child.
                 if *self.point.get_axis(axis) < div {
-                    self.recc(axis.next(), left);
+                    self.recc(left);
                     if self.should_recurse(line) {
-                        self.recc(axis.next(), right);
+                        self.recc(right);
                     }
                 } else {
-                    self.recc(axis.next(), right);
+                    self.recc(right);
                     if self.should_recurse(line) {
-                        self.recc(axis.next(), left);
+                        self.recc(left);
                     }
                 }
 
@@ -409,11 +570,7 @@ mod assert {
                 closest.consider(&point, &mut ktrait, b);
             }
 
-            let num_entries = closest.curr_num;
-            KResult {
-                num_entries,
-                inner: closest.into_sorted(),
-            }
+            closest.into_result()
         }
 
         pub fn find_knearest_closure(
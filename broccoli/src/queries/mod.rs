@@ -1,7 +1,6 @@
 //! Contains query modules for each query algorithm.
 
 use super::*;
-use crate::default_axis;
 use alloc::vec::Vec;
 use compt::*;
 
@@ -11,16 +10,24 @@ pub mod draw;
 
 pub mod knearest;
 
+pub mod radius;
+
 pub mod raycast;
 
+pub mod ccd;
+
 pub mod rect;
 
+pub mod count;
+
 pub mod intersect_with;
 
 mod tools;
 
 pub mod nbody;
 
+pub mod sap;
+
 use core::cmp::Ordering;
 
 ///
@@ -60,6 +67,8 @@ impl<'a, T: Aabb> Tree<'a, T> {
     /// a subset of all colliding pairs by using the colliding pair building blocks
     /// in [`queries::colfind::build`]
     ///
+    /// See [`Tree::stats()`] for the numbers behind this.
+    ///
     #[must_use]
     pub fn is_degenerate(&self) -> bool {
         let tree = self;
@@ -85,6 +94,49 @@ impl<'a, T: Aabb> Tree<'a, T> {
     }
 }
 
+impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// Query the tree through a shared reference. See [`TreeRef`]
+    ///
+    #[must_use]
+    #[inline(always)]
+    pub fn read_only(&self) -> TreeRef<'a, '_, T> {
+        TreeRef { tree: self }
+    }
+}
+
+///
+/// A tree whose queries only return shared references to its elements.
+///
+/// It can be copied freely, and sent to other threads if the elements are [`Sync`],
+/// so that one tree built per frame can be queried from many threads at the same time.
+///
+pub struct TreeRef<'a, 'b, T: Aabb> {
+    tree: &'b Tree<'a, T>,
+}
+
+impl<'a, 'b, T: Aabb> Clone for TreeRef<'a, 'b, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<'a, 'b, T: Aabb> Copy for TreeRef<'a, 'b, T> {}
+
+impl<'a, 'b, T: Aabb> TreeRef<'a, 'b, T> {
+    #[must_use]
+    #[inline(always)]
+    pub fn vistr(&self) -> Vistr<'b, Node<'a, T, T::Num>> {
+        self.tree.vistr()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_nodes(&self) -> &'b [Node<'a, T, T::Num>] {
+        self.tree.get_nodes()
+    }
+}
+
 ///
 /// Iterate over every pair regardless if colliding or not.
 ///
@@ -186,16 +186,15 @@ fn recc_common<'a, 'b, N: Nbody>(
     }
 }
 
-fn recc<N: Nbody>(
-    axis: impl Axis,
-    vistr: VistrMut<NodeWrapper<N::T, N::Mass>, PreOrder>,
-    no: &mut N,
-) {
-    let keep_going = recc_common(axis, vistr, no);
+fn recc<N: Nbody>(vistr: VistrMut<NodeWrapper<N::T, N::Mass>, PreOrder>, no: &mut N) {
+    let keep_going = match vistr.borrow().next().0.node.axis {
+        AxisDyn::X => recc_common(XAXIS, vistr, no),
+        AxisDyn::Y => recc_common(YAXIS, vistr, no),
+    };
 
     if let Some([left, right]) = keep_going {
-        recc(axis.next(), left, no);
-        recc(axis.next(), right, no);
+        recc(left, no);
+        recc(right, no);
     }
 }
 
@@ -247,7 +246,7 @@ impl<'a, T: Aabb> crate::Tree<'a, T> {
             //calculate node masses of each node.
             build_masses2(vistr.borrow_mut(), no);
 
-            recc(default_axis(), vistr.borrow_mut(), no);
+            recc(vistr.borrow_mut(), no);
 
             apply_tree(vistr, no);
 
@@ -0,0 +1,178 @@
+//!
+//! Find all elements within a distance of a point.
+//!
+//! The distances are the ones given by a [`Knearest`], so with [`AabbKnearest`]
+//! the radius is the squared distance to the rect of an element.
+//!
+//! ```rust
+//! use broccoli::queries::knearest::AabbKnearest;
+//! use broccoli::rect;
+//!
+//! let mut aabbs = [rect(0, 10, 0, 10), rect(20, 30, 0, 10), rect(50, 60, 50, 60)];
+//!
+//! let mut tree = broccoli::Tree::new(&mut aabbs);
+//!
+//! let mut found = vec![];
+//! tree.find_all_in_radius(axgeom::vec2(15, 5), 5 * 5, AabbKnearest, |a, dis| {
+//!     found.push((*a, dis))
+//! });
+//! found.sort_by_key(|a| a.0.x.start);
+//! assert_eq!(found, [(rect(0, 10, 0, 10), 25), (rect(20, 30, 0, 10), 25)]);
+//! ```
+//!
+
+use super::*;
+use knearest::Knearest;
+
+#[cfg(doc)]
+use knearest::AabbKnearest;
+
+impl<'a, T: Aabb> Tree<'a, T> {
+    ///
+    /// Find every element whose distance from the point is at most the radius,
+    /// along with that distance. Subtrees and nodes are skipped when the distance
+    /// to their dividing line or to their bounds along it is already too far.
+    ///
+    pub fn find_all_in_radius<'b>(
+        &'b mut self,
+        point: Vec2<T::Num>,
+        radius: T::Num,
+        mut ktrait: impl Knearest<T>,
+        mut func: impl FnMut(AabbPin<&'b mut T>, T::Num),
+    ) {
+        recurse(self.vistr_mut(), point, radius, &mut ktrait, &mut func);
+    }
+}
+
+fn recurse<'a, 'b: 'a, T: Aabb, K: Knearest<T>, F: FnMut(AabbPin<&'a mut T>, T::Num)>(
+    m: VistrMutPin<'a, Node<'b, T, T::Num>>,
+    point: Vec2<T::Num>,
+    radius: T::Num,
+    knear: &mut K,
+    func: &mut F,
+) {
+    let (nn, rest) = m.next();
+    let (handle_node, [handle_left, handle_right]) =
+        reach(&nn, rest.is_none(), point, radius, knear);
+
+    if let Some([left, right]) = rest {
+        if handle_left {
+            recurse(left, point, radius, knear, func);
+        }
+        if handle_right {
+            recurse(right, point, radius, knear, func);
+        }
+    }
+
+    if handle_node {
+        for b in nn.into_range().iter_mut() {
+            consider(point, radius, knear, b, func);
+        }
+    }
+}
+
+//Whether the elements of the node and of each of its children may be within
+//the radius. Subtrees and nodes are skipped when the distance to their dividing
+//line or to their bounds along it is already too far.
+pub(super) fn reach<T: Aabb, K: Knearest<T>>(
+    nn: &Node<T, T::Num>,
+    is_leaf: bool,
+    point: Vec2<T::Num>,
+    radius: T::Num,
+    knear: &mut K,
+) -> (bool, [bool; 2]) {
+    if is_leaf {
+        return (true, [false, false]);
+    }
+
+    let div = match nn.div {
+        Some(b) => b,
+        None => return (false, [false, false]),
+    };
+
+    let axis = nn.axis;
+    let p = *point.get_axis(axis);
+
+    //Every element of a child is on the far side of the divider from
+    //the point, if the point is not on the side of that child.
+    let too_far = knear.distance_to_aaline(point, axis, div) > radius;
+    let children = [!(p > div && too_far), !(p < div && too_far)];
+
+    let handle_node = !nn.range.is_empty()
+        && match nn.cont.contains_ext(p) {
+            core::cmp::Ordering::Less => {
+                knear.distance_to_aaline(point, axis, nn.cont.start) <= radius
+            }
+            core::cmp::Ordering::Greater => {
+                knear.distance_to_aaline(point, axis, nn.cont.end) <= radius
+            }
+            core::cmp::Ordering::Equal => true,
+        };
+
+    (handle_node, children)
+}
+
+#[inline(always)]
+pub(super) fn consider<'a, T: Aabb, K: Knearest<T>>(
+    point: Vec2<T::Num>,
+    radius: T::Num,
+    knear: &mut K,
+    mut b: AabbPin<&'a mut T>,
+    func: &mut impl FnMut(AabbPin<&'a mut T>, T::Num),
+) {
+    if let Some(broad) = knear.distance_to_broad(point, b.borrow_mut()) {
+        if broad > radius {
+            return;
+        }
+    }
+    let dis = knear.distance_to_fine(point, b.borrow_mut());
+    if dis <= radius {
+        func(b, dis);
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb> Naive<'a, T> {
+        pub fn find_all_in_radius<'b>(
+            &'b mut self,
+            point: Vec2<T::Num>,
+            radius: T::Num,
+            mut ktrait: impl Knearest<T>,
+            mut func: impl FnMut(AabbPin<&'b mut T>, T::Num),
+        ) {
+            for b in self.iter_mut() {
+                consider(point, radius, &mut ktrait, b, &mut func);
+            }
+        }
+    }
+
+    impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_radius(
+            &mut self,
+            point: Vec2<T::Num>,
+            radius: T::Num,
+            mut knear: impl Knearest<T>,
+        ) {
+            use crate::assert::into_ptr_usize;
+
+            let mut res_dino = vec![];
+            Tree::new(self.inner).find_all_in_radius(point, radius, &mut knear, |a, dis| {
+                res_dino.push((into_ptr_usize(a), dis))
+            });
+
+            let mut res_naive = vec![];
+            Naive::new(self.inner).find_all_in_radius(point, radius, knear, |a, dis| {
+                res_naive.push((into_ptr_usize(a), dis))
+            });
+
+            res_naive.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            res_dino.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+        }
+    }
+}
@@ -143,7 +143,7 @@ impl<'a, T: Aabb> Tree<'a, T> {
         struct Recurser<'a, T: Aabb, R: RayCast<T>> {
             rtrait: R,
             ray: Ray<T::Num>,
-            closest: Closest<'a, T>,
+            closest: Closest<AabbPin<&'a mut T>, T::Num>,
         }
 
         impl<'a, T: Aabb, R: RayCast<T>> Recurser<'a, T, R> {
@@ -158,15 +158,21 @@ impl<'a, T: Aabb> Tree<'a, T> {
             }
 
             //Returns the first object that touches the ray.
-            fn recc<'b: 'a, A: Axis>(
+            fn recc<'b: 'a>(&mut self, stuff: LevelIter<VistrMutPin<'a, Node<'b, T, T::Num>>>) {
+                let ((_depth, nn), rest) = stuff.next();
+                match nn.axis {
+                    AxisDyn::X => self.recc_axis(XAXIS, nn, rest),
+                    AxisDyn::Y => self.recc_axis(YAXIS, nn, rest),
+                }
+            }
+
+            fn recc_axis<'b: 'a, A: Axis>(
                 &mut self,
                 axis: A,
-                stuff: LevelIter<VistrMutPin<'a, Node<'b, T, T::Num>>>,
+                nn: AabbPin<&'a mut Node<'b, T, T::Num>>,
+                rest: LevelRest<VistrMutPin<'a, Node<'b, T, T::Num>>>,
             ) {
-                let ((_depth, nn), rest) = stuff.next();
                 let handle_curr = if let Some([left, right]) = rest {
-                    let axis_next = axis.next();
-
                     let div = match nn.div {
                         Some(b) => b,
                         None => return,
@@ -177,16 +183,16 @@ impl<'a, T: Aabb> Tree<'a, T> {
                     //more likely to find closest in child than current node.
                     //so recurse first before handling this node.
                     if *self.ray.point.get_axis(axis) < div {
-                        self.recc(axis_next, left);
+                        self.recc(left);
 
                         if self.should_recurse(line) {
-                            self.recc(axis_next, right);
+                            self.recc(right);
                         }
                     } else {
-                        self.recc(axis_next, right);
+                        self.recc(right);
 
                         if self.should_recurse(line) {
-                            self.recc(axis_next, left);
+                            self.recc(left);
                         }
                     }
 
@@ -222,7 +228,7 @@ impl<'a, T: Aabb> Tree<'a, T> {
             ray,
             closest,
         };
-        rec.recc(default_axis(), dt);
+        rec.recc(dt);
 
         match rec.closest.closest {
             Some((a, b)) => axgeom::CastResult::Hit(CastAnswer { elems: a, mag: b }),
@@ -254,10 +260,160 @@ impl<T: Aabb, R: RayCast<T>> RayCast<T> for &mut R {
     }
 }
 
-struct Closest<'a, T: Aabb> {
-    closest: Option<(Vec<AabbPin<&'a mut T>>, T::Num)>,
+///
+/// Like [`RayCast`] except it is only given shared references to the elements.
+/// See [`TreeRef::cast_ray()`]
+///
+pub trait RayCastRef<T: Aabb> {
+    fn cast_to_aaline<A: Axis>(
+        &mut self,
+        ray: &Ray<T::Num>,
+        line: A,
+        val: T::Num,
+    ) -> axgeom::CastResult<T::Num>;
+
+    fn cast_broad(&mut self, ray: &Ray<T::Num>, a: &T) -> Option<axgeom::CastResult<T::Num>>;
+
+    fn cast_fine(&mut self, ray: &Ray<T::Num>, a: &T) -> axgeom::CastResult<T::Num>;
+}
+
+impl<T: Aabb> RayCastRef<T> for AabbRaycast
+where
+    T::Num: core::fmt::Debug + num_traits::Signed,
+{
+    fn cast_to_aaline<A: Axis>(
+        &mut self,
+        ray: &Ray<T::Num>,
+        line: A,
+        val: T::Num,
+    ) -> axgeom::CastResult<T::Num> {
+        ray.cast_to_aaline(line, val)
+    }
+
+    fn cast_broad(&mut self, _ray: &Ray<T::Num>, _a: &T) -> Option<axgeom::CastResult<T::Num>> {
+        None
+    }
+
+    fn cast_fine(&mut self, ray: &Ray<T::Num>, a: &T) -> axgeom::CastResult<T::Num> {
+        ray.cast_to_rect(a.get())
+    }
+}
+
+impl<T: Aabb, R: RayCastRef<T>> RayCastRef<T> for &mut R {
+    fn cast_to_aaline<A: Axis>(
+        &mut self,
+        ray: &Ray<T::Num>,
+        line: A,
+        val: T::Num,
+    ) -> axgeom::CastResult<T::Num> {
+        (*self).cast_to_aaline(ray, line, val)
+    }
+
+    fn cast_broad(&mut self, ray: &Ray<T::Num>, a: &T) -> Option<axgeom::CastResult<T::Num>> {
+        (*self).cast_broad(ray, a)
+    }
+
+    fn cast_fine(&mut self, ray: &Ray<T::Num>, a: &T) -> axgeom::CastResult<T::Num> {
+        (*self).cast_fine(ray, a)
+    }
+}
+
+impl<'a, 'b, T: Aabb> TreeRef<'a, 'b, T> {
+    ///
+    /// Like [`Tree::cast_ray()`] except shared references to the closest elements are returned.
+    ///
+    pub fn cast_ray(
+        &self,
+        ray: Ray<T::Num>,
+        mut rtrait: impl RayCastRef<T>,
+    ) -> axgeom::CastResult<(Vec<&'b T>, T::Num)> {
+        let mut closest = Closest { closest: None };
+        recc_ref(self.vistr(), &ray, &mut rtrait, &mut closest);
+
+        match closest.closest {
+            Some(a) => axgeom::CastResult::Hit(a),
+            None => axgeom::CastResult::NoHit,
+        }
+    }
+}
+
+//Same as the Recurser of Tree::cast_ray() except with shared references.
+fn recc_ref<'b, T: Aabb, R: RayCastRef<T>>(
+    m: Vistr<'b, Node<T, T::Num>>,
+    ray: &Ray<T::Num>,
+    rtrait: &mut R,
+    closest: &mut Closest<&'b T, T::Num>,
+) {
+    fn should_recurse<T: Aabb, R: RayCastRef<T>, A: Axis>(
+        ray: &Ray<T::Num>,
+        rtrait: &mut R,
+        closest: &Closest<&T, T::Num>,
+        line: (A, T::Num),
+    ) -> bool {
+        match rtrait.cast_to_aaline(ray, line.0, line.1) {
+            axgeom::CastResult::Hit(val) => match closest.get_dis() {
+                Some(dis) => val <= dis,
+                None => true,
+            },
+            axgeom::CastResult::NoHit => false,
+        }
+    }
+
+    let (nn, rest) = m.next();
+    let axis = nn.axis;
+
+    let handle_curr = if let Some([left, right]) = rest {
+        let div = match nn.div {
+            Some(b) => b,
+            None => return,
+        };
+
+        let line = (axis, div);
+
+        //more likely to find closest in child than current node.
+        //so recurse first before handling this node.
+        if *ray.point.get_axis(axis) < div {
+            recc_ref(left, ray, rtrait, closest);
+            if should_recurse(ray, rtrait, closest, line) {
+                recc_ref(right, ray, rtrait, closest);
+            }
+        } else {
+            recc_ref(right, ray, rtrait, closest);
+            if should_recurse(ray, rtrait, closest, line) {
+                recc_ref(left, ray, rtrait, closest);
+            }
+        }
+
+        if !nn.range.is_empty() {
+            match nn.cont.contains_ext(*ray.point.get_axis(axis)) {
+                core::cmp::Ordering::Less => {
+                    should_recurse(ray, rtrait, closest, (axis, nn.cont.start))
+                }
+                core::cmp::Ordering::Greater => {
+                    should_recurse(ray, rtrait, closest, (axis, nn.cont.end))
+                }
+                core::cmp::Ordering::Equal => true,
+            }
+        } else {
+            false
+        }
+    } else {
+        true
+    };
+
+    if handle_curr {
+        for b in nn.range.iter() {
+            closest.consider_ref(ray, b, rtrait);
+        }
+    }
+}
+
+//Generic over the bot so that it can hold shared references as well.
+struct Closest<B, N> {
+    closest: Option<(Vec<B>, N)>,
 }
-impl<'a, T: Aabb> Closest<'a, T> {
+
+impl<'a, T: Aabb> Closest<AabbPin<&'a mut T>, T::Num> {
     fn consider<R: RayCast<T>>(
         &mut self,
         ray: &Ray<T::Num>,
@@ -266,32 +422,45 @@ impl<'a, T: Aabb> Closest<'a, T> {
     ) {
         //first check if bounding box could possibly be a candidate.
         if let Some(broad) = raytrait.cast_broad(ray, b.borrow_mut()) {
-            let y = match broad {
-                axgeom::CastResult::Hit(val) => val,
-                axgeom::CastResult::NoHit => {
-                    return;
-                }
-            };
-
-            if let Some(dis) = self.closest.as_mut() {
-                if y > dis.1 {
-                    //no way this bot will be a candidate, return.
-                    return;
-                } else {
-                    //this aabb could be a candidate, continue.
-                }
-            } else {
-                //this aabb could be a candidate, continue,
+            if self.is_too_far(broad) {
+                return;
             }
         }
 
-        let x = match raytrait.cast_fine(ray, b.borrow_mut()) {
-            axgeom::CastResult::Hit(val) => val,
-            axgeom::CastResult::NoHit => {
+        if let axgeom::CastResult::Hit(x) = raytrait.cast_fine(ray, b.borrow_mut()) {
+            self.insert(b, x);
+        }
+    }
+}
+
+impl<'b, T: Aabb> Closest<&'b T, T::Num> {
+    fn consider_ref<R: RayCastRef<T>>(&mut self, ray: &Ray<T::Num>, b: &'b T, raytrait: &mut R) {
+        if let Some(broad) = raytrait.cast_broad(ray, b) {
+            if self.is_too_far(broad) {
                 return;
             }
-        };
+        }
+
+        if let axgeom::CastResult::Hit(x) = raytrait.cast_fine(ray, b) {
+            self.insert(b, x);
+        }
+    }
+}
+
+impl<B, N: PartialOrd + Copy> Closest<B, N> {
+    fn is_too_far(&self, broad: axgeom::CastResult<N>) -> bool {
+        match broad {
+            //no way this bot will be a candidate.
+            axgeom::CastResult::NoHit => true,
+            axgeom::CastResult::Hit(y) => match self.closest.as_ref() {
+                Some(dis) => y > dis.1,
+                //this aabb could be a candidate.
+                None => false,
+            },
+        }
+    }
 
+    fn insert(&mut self, b: B, x: N) {
         match self.closest.as_mut() {
             Some(mut dis) => {
                 if x > dis.1 {
@@ -308,7 +477,7 @@ impl<'a, T: Aabb> Closest<'a, T> {
         };
     }
 
-    fn get_dis(&self) -> Option<T::Num> {
+    fn get_dis(&self) -> Option<N> {
         self.closest.as_ref().map(|x| x.1)
     }
 }
@@ -11,19 +11,19 @@ impl<'a, T: Aabb> crate::Tree<'a, T> {
         fn rect_recurse<
             'a,
             'b: 'a,
-            A: Axis,
             T: Aabb,
             K: Aabb<Num = T::Num>,
             F: FnMut(AabbPin<&mut K>, AabbPin<&'a mut T>),
         >(
-            axis: A,
             it: VistrMutPin<'a, Node<'b, T, T::Num>>,
             mut rect: AabbPin<&mut K>,
             closure: &mut F,
         ) {
             let (nn, rest) = it.next();
 
-            let NodeRef { div, range, .. } = nn.into_node_ref();
+            let NodeRef {
+                axis, div, range, ..
+            } = nn.into_node_ref();
 
             for a in range.iter_mut() {
                 if !rect.get().contains_rect(a.get()) {
@@ -37,14 +37,19 @@ impl<'a, T: Aabb> crate::Tree<'a, T> {
                     None => return,
                 };
 
-                match rect.range(axis).contains_ext(*div) {
+                let r = match axis {
+                    AxisDyn::X => rect.range(XAXIS),
+                    AxisDyn::Y => rect.range(YAXIS),
+                };
+
+                match r.contains_ext(*div) {
                     core::cmp::Ordering::Greater => {
                         for a in right.into_slice() {
                             for b in a.into_range().iter_mut() {
                                 closure(rect.borrow_mut(), b)
                             }
                         }
-                        rect_recurse(axis.next(), left, rect, closure)
+                        rect_recurse(left, rect, closure)
                     }
                     core::cmp::Ordering::Less => {
                         for a in left.into_slice() {
@@ -52,16 +57,16 @@ impl<'a, T: Aabb> crate::Tree<'a, T> {
                                 closure(rect.borrow_mut(), b)
                             }
                         }
-                        rect_recurse(axis.next(), right, rect, closure)
+                        rect_recurse(right, rect, closure)
                     }
                     core::cmp::Ordering::Equal => {
-                        rect_recurse(axis.next(), left, rect.borrow_mut(), closure);
-                        rect_recurse(axis.next(), right, rect.borrow_mut(), closure)
+                        rect_recurse(left, rect.borrow_mut(), closure);
+                        rect_recurse(right, rect.borrow_mut(), closure)
                     }
                 }
             }
         }
-        rect_recurse(default_axis(), self.vistr_mut(), rect, &mut closure);
+        rect_recurse(self.vistr_mut(), rect, &mut closure);
     }
 
     pub fn find_all_in_rect<'b, K: Aabb<Num = T::Num>>(
@@ -69,11 +74,18 @@ impl<'a, T: Aabb> crate::Tree<'a, T> {
         rect: AabbPin<&mut K>,
         mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
     ) {
-        rect_recurse(default_axis(), self.vistr_mut(), rect, &mut |r, a| {
-            if r.get().contains_rect(a.get()) {
-                closure(r, a);
-            }
-        });
+        //Elements with no size on the border of the rect are contained by it
+        //even if they are half open, so they must not be cut from the sections.
+        rect_recurse(
+            self.vistr_mut(),
+            rect,
+            IntersectionMode::Closed,
+            &mut |r, a| {
+                if r.get().contains_rect(a.get()) {
+                    closure(r, a);
+                }
+            },
+        );
     }
 
     pub fn find_all_intersect_rect<'b, K: Aabb<Num = T::Num>>(
@@ -81,19 +93,246 @@ impl<'a, T: Aabb> crate::Tree<'a, T> {
         rect: AabbPin<&mut K>,
         mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
     ) {
-        rect_recurse(default_axis(), self.vistr_mut(), rect, &mut |r, a| {
-            if r.get().get_intersect_rect(a.get()).is_some() {
+        let mode = T::INTERSECTION_MODE.stricter(K::INTERSECTION_MODE);
+        rect_recurse(self.vistr_mut(), rect, T::INTERSECTION_MODE, &mut |r, a| {
+            if mode.intersects_rect(r.get(), a.get()) {
                 closure(r, a);
             }
         });
     }
 }
 
-use super::tools::get_section_mut;
+impl<'a, T: Aabb> crate::Tree<'a, T> {
+    ///
+    /// Find every element that intersects a convex polygon.
+    ///
+    /// A convex polygon crosses a divider exactly when its extent along the
+    /// divider's axis does, so subtrees and nodes are pruned with the bounding
+    /// rect of the polygon. Every element whose rect intersects that bounding
+    /// rect is then passed to the `fine` test along with the polygon, and
+    /// handed to the closure if it returns true.
+    ///
+    pub fn find_all_intersect_convex<'b>(
+        &'b mut self,
+        polygon: &[Vec2<T::Num>],
+        mut fine: impl FnMut(&[Vec2<T::Num>], AabbPin<&mut T>) -> bool,
+        mut closure: impl FnMut(AabbPin<&'b mut T>),
+    ) {
+        let mut bound = match bounding_rect(polygon) {
+            Some(r) => r,
+            None => return,
+        };
+        let mode = T::INTERSECTION_MODE;
+        rect_recurse(
+            self.vistr_mut(),
+            AabbPin::new(&mut bound),
+            mode,
+            &mut |r, mut a| {
+                if mode.intersects_rect(r.get(), a.get()) && fine(polygon, a.borrow_mut()) {
+                    closure(a);
+                }
+            },
+        );
+    }
+}
+
+impl<'a, T: Aabb> crate::Tree<'a, T> {
+    ///
+    /// Find every element whose rect contains the point.
+    /// See [`IntersectionMode::contains_point()`]
+    ///
+    /// Only the side of each divider that the point is on is visited, since
+    /// no element of a child reaches its divider. The elements of a node are
+    /// only checked if the point is within the node's container range.
+    ///
+    pub fn find_all_containing_point<'b>(
+        &'b mut self,
+        point: Vec2<T::Num>,
+        mut closure: impl FnMut(AabbPin<&'b mut T>),
+    ) {
+        point_recurse(self.vistr_mut(), point, &mut closure);
+    }
+}
+
+fn point_recurse<'a, 'b: 'a, T: Aabb, F: FnMut(AabbPin<&'a mut T>)>(
+    m: VistrMutPin<'a, Node<'b, T, T::Num>>,
+    point: Vec2<T::Num>,
+    func: &mut F,
+) {
+    let (nn, rest) = m.next();
+    let NodeRef {
+        axis,
+        div,
+        cont,
+        range,
+    } = nn.into_node_ref();
+
+    let div = match (div, &rest) {
+        (Some(div), Some(_)) => Some(*div),
+        (None, Some(_)) => return,
+        (_, None) => None,
+    };
+
+    let p = *point.get_axis(axis);
+    if div.is_none() || cont.contains_ext(p) == core::cmp::Ordering::Equal {
+        let q = *point.get_axis(axis.next());
+        //Points on the end of half open rects are only skipped once the rect is checked.
+        let range = get_section_mut_mode(
+            axis.next(),
+            range,
+            &Range::new(q, q),
+            IntersectionMode::Closed,
+        );
+        for a in range.iter_mut() {
+            if T::INTERSECTION_MODE.contains_point(a.get(), point) {
+                func(a);
+            }
+        }
+    }
+
+    if let (Some(div), Some([left, right])) = (div, rest) {
+        if p < div {
+            point_recurse(left, point, func);
+        } else if p > div {
+            point_recurse(right, point, func);
+        }
+    }
+}
+
+fn point_recurse_ref<'b, T: Aabb, F: FnMut(&'b T)>(
+    m: Vistr<'b, Node<T, T::Num>>,
+    point: Vec2<T::Num>,
+    func: &mut F,
+) {
+    let (nn, rest) = m.next();
+    let axis = nn.axis;
+
+    let div = match (nn.div, &rest) {
+        (Some(div), Some(_)) => Some(div),
+        (None, Some(_)) => return,
+        (_, None) => None,
+    };
+
+    let p = *point.get_axis(axis);
+    if div.is_none() || nn.cont.contains_ext(p) == core::cmp::Ordering::Equal {
+        let q = *point.get_axis(axis.next());
+        let range = &Range::new(q, q);
+        for a in get_section(axis.next(), &nn.range, range, IntersectionMode::Closed) {
+            if T::INTERSECTION_MODE.contains_point(a.get(), point) {
+                func(a);
+            }
+        }
+    }
+
+    if let (Some(div), Some([left, right])) = (div, rest) {
+        if p < div {
+            point_recurse_ref(left, point, func);
+        } else if p > div {
+            point_recurse_ref(right, point, func);
+        }
+    }
+}
+
+fn bounding_rect<N: Num>(polygon: &[Vec2<N>]) -> Option<Rect<N>> {
+    let (first, rest) = polygon.split_first()?;
+    let mut r = Rect::new(first.x, first.x, first.y, first.y);
+    for p in rest {
+        if p.x < r.x.start {
+            r.x.start = p.x;
+        }
+        if p.x > r.x.end {
+            r.x.end = p.x;
+        }
+        if p.y < r.y.start {
+            r.y.start = p.y;
+        }
+        if p.y > r.y.end {
+            r.y.end = p.y;
+        }
+    }
+    Some(r)
+}
+
+impl<'a, 'b, T: Aabb> TreeRef<'a, 'b, T> {
+    pub fn find_all_in_rect(&self, rect: &Rect<T::Num>, mut closure: impl FnMut(&'b T)) {
+        //See Tree::find_all_in_rect()
+        rect_recurse_ref(self.vistr(), rect, IntersectionMode::Closed, &mut |a| {
+            if rect.contains_rect(a.get()) {
+                closure(a);
+            }
+        });
+    }
+
+    pub fn find_all_intersect_rect(&self, rect: &Rect<T::Num>, mut closure: impl FnMut(&'b T)) {
+        rect_recurse_ref(self.vistr(), rect, T::INTERSECTION_MODE, &mut |a| {
+            if T::INTERSECTION_MODE.intersects_rect(rect, a.get()) {
+                closure(a);
+            }
+        });
+    }
+
+    ///
+    /// Find every element whose rect contains the point.
+    /// See [`IntersectionMode::contains_point()`]
+    ///
+    pub fn find_all_containing_point(&self, point: Vec2<T::Num>, mut closure: impl FnMut(&'b T)) {
+        point_recurse_ref(self.vistr(), point, &mut closure);
+    }
+}
+
+fn rect_recurse_ref<'b, T: Aabb, F: FnMut(&'b T)>(
+    m: Vistr<'b, Node<T, T::Num>>,
+    rect: &Rect<T::Num>,
+    mode: IntersectionMode,
+    func: &mut F,
+) {
+    let (nn, rest) = m.next();
+    let axis = nn.axis;
+
+    if let Some([left, right]) = rest {
+        let div = match nn.div {
+            Some(b) => b,
+            None => return,
+        };
+
+        for a in get_section(axis.next(), &nn.range, rect.get_range(axis.next()), mode) {
+            func(a);
+        }
+
+        if div >= rect.get_range(axis).start {
+            rect_recurse_ref(left, rect, mode, func);
+        }
+        if div <= rect.get_range(axis).end {
+            rect_recurse_ref(right, rect, mode, func);
+        }
+    } else {
+        for a in get_section(axis.next(), &nn.range, rect.get_range(axis.next()), mode) {
+            func(a);
+        }
+    }
+}
+
+use super::tools::{get_section, get_section_mut_mode};
 fn foo<'a, 'b: 'a, T: Aabb>(node: AabbPin<&'a mut Node<'b, T, T::Num>>) -> AabbPin<&'a mut [T]> {
     node.into_range()
 }
 fn rect_recurse<
+    'a,
+    T: Aabb,
+    F: FnMut(AabbPin<&mut K>, AabbPin<&'a mut T>),
+    K: Aabb<Num = T::Num>,
+>(
+    m: VistrMutPin<'a, Node<T, T::Num>>,
+    rect: AabbPin<&mut K>,
+    mode: IntersectionMode,
+    func: &mut F,
+) {
+    match m.borrow().next().0.axis {
+        AxisDyn::X => rect_recurse_axis(XAXIS, m, rect, mode, func),
+        AxisDyn::Y => rect_recurse_axis(YAXIS, m, rect, mode, func),
+    }
+}
+fn rect_recurse_axis<
     'a,
     A: Axis,
     T: Aabb,
@@ -103,6 +342,7 @@ fn rect_recurse<
     this_axis: A,
     m: VistrMutPin<'a, Node<T, T::Num>>,
     mut rect: AabbPin<&mut K>,
+    mode: IntersectionMode,
     func: &mut F,
 ) {
     let (nn, rest) = m.next();
@@ -114,21 +354,31 @@ fn rect_recurse<
                 None => return,
             };
 
-            let sl = get_section_mut(this_axis.next(), foo(nn), rect.range(this_axis.next()));
+            let sl = get_section_mut_mode(
+                this_axis.next(),
+                foo(nn),
+                rect.range(this_axis.next()),
+                mode,
+            );
 
             for i in sl {
                 func(rect.borrow_mut(), i);
             }
 
             if div >= rect.range(this_axis).start {
-                self::rect_recurse(this_axis.next(), left, rect.borrow_mut(), func);
+                self::rect_recurse(left, rect.borrow_mut(), mode, func);
             }
             if div <= rect.range(this_axis).end {
-                self::rect_recurse(this_axis.next(), right, rect, func);
+                self::rect_recurse(right, rect, mode, func);
             }
         }
         None => {
-            let sl = get_section_mut(this_axis.next(), foo(nn), rect.range(this_axis.next()));
+            let sl = get_section_mut_mode(
+                this_axis.next(),
+                foo(nn),
+                rect.range(this_axis.next()),
+                mode,
+            );
 
             for i in sl {
                 func(rect.borrow_mut(), i);
@@ -148,6 +398,26 @@ mod assert {
     impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
         ///Panics if a disconnect is detected between tree and naive queries.
         pub fn assert_rect(&mut self, rect: axgeom::Rect<T::Num>) {
+            self.assert_rect_mode(rect);
+
+            //The same rects in each intersection mode, along with rects
+            //that have no width or height on the border of the query rect.
+            let Rect { x, y } = rect;
+            let mut closed: Vec<_> = self.inner.iter().map(|a| *a.get()).collect();
+            closed.extend([
+                Rect::new(x.start, x.start, y.start, y.end),
+                Rect::new(x.end, x.end, y.start, y.end),
+                Rect::new(x.start, x.end, y.start, y.start),
+                Rect::new(x.start, x.end, y.end, y.end),
+                Rect::new(x.end, x.end, y.end, y.end),
+            ]);
+            Assert::new(&mut closed).assert_rect_mode(rect);
+
+            let mut half_open: Vec<_> = closed.into_iter().map(HalfOpen).collect();
+            Assert::new(&mut half_open).assert_rect_mode(rect);
+        }
+
+        fn assert_rect_mode(&mut self, rect: axgeom::Rect<T::Num>) {
             self.assert_for_all_not_in_rect_mut(rect);
             self.assert_for_all_intersect_rect_mut(rect);
             self.assert_for_all_in_rect_mut(rect)
@@ -192,6 +462,10 @@ mod assert {
 
         fn assert_for_all_in_rect_mut(&mut self, mut rect: axgeom::Rect<T::Num>) {
             let mut tree = Tree::new(self.inner);
+            let mut res_ref = Vec::new();
+            tree.read_only()
+                .find_all_in_rect(&rect, |a| res_ref.push(into_ptr_usize(a)));
+
             let mut res_dino = Vec::new();
             tree.find_all_in_rect(AabbPin::new(&mut rect), |_, a| {
                 res_dino.push(into_ptr_usize(a.deref()));
@@ -201,6 +475,59 @@ mod assert {
                 res_naive.push(into_ptr_usize(a.deref()));
             });
 
+            res_ref.sort_unstable();
+            res_dino.sort_unstable();
+            res_naive.sort_unstable();
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+            assert!(res_naive.iter().eq(res_ref.iter()));
+        }
+    }
+
+    impl<'a, T: Aabb + ManySwap> Assert<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_containing_point(&mut self, point: Vec2<T::Num>) {
+            let mut tree = Tree::new(self.inner);
+            let mut res_ref = Vec::new();
+            tree.read_only()
+                .find_all_containing_point(point, |a| res_ref.push(into_ptr_usize(a)));
+
+            let mut res_dino = Vec::new();
+            tree.find_all_containing_point(point, |a| {
+                res_dino.push(into_ptr_usize(a.deref()));
+            });
+
+            let mut res_naive = Vec::new();
+            Naive::new(self.inner).find_all_containing_point(point, |a| {
+                res_naive.push(into_ptr_usize(a.deref()));
+            });
+
+            res_ref.sort_unstable();
+            res_dino.sort_unstable();
+            res_naive.sort_unstable();
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+            assert!(res_naive.iter().eq(res_ref.iter()));
+        }
+
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_intersect_convex(
+            &mut self,
+            polygon: &[Vec2<T::Num>],
+            mut fine: impl FnMut(&[Vec2<T::Num>], AabbPin<&mut T>) -> bool,
+        ) {
+            let mut res_dino = Vec::new();
+            Tree::new(self.inner).find_all_intersect_convex(polygon, &mut fine, |a| {
+                res_dino.push(into_ptr_usize(a.deref()));
+            });
+
+            let mut res_naive = Vec::new();
+            Naive::new(self.inner).find_all_intersect_convex(polygon, &mut fine, |a| {
+                res_naive.push(into_ptr_usize(a.deref()));
+            });
+
             res_dino.sort_unstable();
             res_naive.sort_unstable();
 
@@ -210,6 +537,17 @@ mod assert {
     }
 
     impl<'a, T: Aabb> Naive<'a, T> {
+        pub fn find_all_containing_point<'b>(
+            &'b mut self,
+            point: Vec2<T::Num>,
+            mut closure: impl FnMut(AabbPin<&'b mut T>),
+        ) {
+            for b in self.iter_mut() {
+                if T::INTERSECTION_MODE.contains_point(b.get(), point) {
+                    closure(b);
+                }
+            }
+        }
         pub fn find_all_not_in_rect<'b, K: Aabb<Num = T::Num>>(
             &'b mut self,
             mut rect: AabbPin<&mut K>,
@@ -237,11 +575,30 @@ mod assert {
             mut rect: AabbPin<&mut K>,
             mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
         ) {
+            let mode = T::INTERSECTION_MODE.stricter(K::INTERSECTION_MODE);
             for b in self.iter_mut() {
-                if rect.get().get_intersect_rect(b.get()).is_some() {
+                if mode.intersects_rect(rect.get(), b.get()) {
                     closure(rect.borrow_mut(), b);
                 }
             }
         }
+        pub fn find_all_intersect_convex<'b>(
+            &'b mut self,
+            polygon: &[Vec2<T::Num>],
+            mut fine: impl FnMut(&[Vec2<T::Num>], AabbPin<&mut T>) -> bool,
+            mut closure: impl FnMut(AabbPin<&'b mut T>),
+        ) {
+            let bound = match bounding_rect(polygon) {
+                Some(r) => r,
+                None => return,
+            };
+            for mut b in self.iter_mut() {
+                if T::INTERSECTION_MODE.intersects_rect(&bound, b.get())
+                    && fine(polygon, b.borrow_mut())
+                {
+                    closure(b);
+                }
+            }
+        }
     }
 }
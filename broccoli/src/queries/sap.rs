@@ -0,0 +1,312 @@
+//!
+//! A persistent sweep and prune for scenes with few elements that move a little every frame.
+//!
+//! Unlike a [`Tree`], which is rebuilt every frame, [`SweepAndPrune`] keeps the
+//! endpoints of every element sorted along both axes across frames and
+//! keeps the set of overlapping pairs up to date with the swaps of an
+//! insertion sort. When elements barely move, few swaps are needed.
+//!
+//! ```rust
+//! use broccoli::queries::sap::SweepAndPrune;
+//! use broccoli::rect;
+//!
+//! let mut aabbs = [rect(0, 10, 0, 10), rect(20, 30, 0, 10)];
+//!
+//! let mut sap = SweepAndPrune::new(&mut aabbs);
+//!
+//! let mut num = 0;
+//! sap.find_colliding_pairs(|_, _| num += 1);
+//! assert_eq!(num, 0);
+//!
+//! sap.get_elements_mut()[1] = rect(5, 15, 0, 10);
+//!
+//! let mut num = 0;
+//! sap.find_colliding_pairs(|_, _| num += 1);
+//! assert_eq!(num, 1);
+//! ```
+//!
+
+use super::*;
+use alloc::collections::BTreeSet;
+use core::ops::ControlFlow;
+
+#[derive(Copy, Clone, Debug)]
+struct Endpoint<N> {
+    value: N,
+    index: usize,
+    is_end: bool,
+}
+
+impl<N: PartialOrd> Endpoint<N> {
+    //Ties are broken so that two ranges overlap exactly when
+    //each starts before the other ends.
+    #[inline(always)]
+    fn is_before(&self, other: &Self, mode: IntersectionMode) -> bool {
+        self.value < other.value
+            || (self.value == other.value
+                && self.is_end != other.is_end
+                && self.is_end != mode.is_closed())
+    }
+}
+
+///
+/// Sweep and prune that keeps its sorted endpoints and overlapping pairs between queries.
+///
+/// The elements are never reordered, so they do not need to be [`ManySwap`],
+/// and the pairs are reported by their position in the slice.
+///
+pub struct SweepAndPrune<'a, T: Aabb> {
+    bots: &'a mut [T],
+    axes: [Vec<Endpoint<T::Num>>; 2],
+    //Index pairs with the smaller index first.
+    pairs: BTreeSet<(usize, usize)>,
+    //Set when the elements may have moved since the last update.
+    stale: bool,
+}
+
+impl<'a, T: Aabb> SweepAndPrune<'a, T> {
+    pub fn new(bots: &'a mut [T]) -> Self {
+        let mode = T::INTERSECTION_MODE;
+
+        let endpoints = |axis: AxisDyn| {
+            let mut v: Vec<_> = bots
+                .iter()
+                .enumerate()
+                .flat_map(|(index, a)| {
+                    let r = a.get().get_range(axis);
+                    [
+                        Endpoint {
+                            value: r.start,
+                            index,
+                            is_end: false,
+                        },
+                        Endpoint {
+                            value: r.end,
+                            index,
+                            is_end: true,
+                        },
+                    ]
+                })
+                .collect();
+            v.sort_by(|a, b| {
+                if a.is_before(b, mode) {
+                    Ordering::Less
+                } else if b.is_before(a, mode) {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            });
+            v
+        };
+
+        let axes = [endpoints(AxisDyn::X), endpoints(AxisDyn::Y)];
+
+        let mut pairs = BTreeSet::new();
+        let mut active: Vec<usize> = vec![];
+        for e in axes[0].iter() {
+            if e.is_end {
+                active.retain(|&i| i != e.index);
+            } else {
+                let a = bots[e.index].get();
+                for &i in active.iter() {
+                    if mode.intersects_rect(a, bots[i].get()) {
+                        pairs.insert(ordered(i, e.index));
+                    }
+                }
+                active.push(e.index);
+            }
+        }
+
+        SweepAndPrune {
+            bots,
+            axes,
+            pairs,
+            stale: false,
+        }
+    }
+
+    ///
+    /// The elements may be moved freely through the returned slice.
+    /// The next query brings the overlapping pairs up to date.
+    ///
+    pub fn get_elements_mut(&mut self) -> &mut [T] {
+        self.stale = true;
+        self.bots
+    }
+
+    #[must_use]
+    pub fn get_elements(&self) -> &[T] {
+        self.bots
+    }
+
+    ///
+    /// Re-sort the endpoints along both axes, adding and removing
+    /// overlapping pairs as endpoints swap past each other.
+    /// Queries call this on their own if the elements may have moved.
+    ///
+    pub fn update(&mut self) {
+        let bots = &*self.bots;
+        for (axis, list) in [AxisDyn::X, AxisDyn::Y]
+            .into_iter()
+            .zip(self.axes.iter_mut())
+        {
+            for e in list.iter_mut() {
+                let r = bots[e.index].get().get_range(axis);
+                e.value = if e.is_end { r.end } else { r.start };
+            }
+            insertion_sort(bots, list, &mut self.pairs);
+        }
+        self.stale = false;
+    }
+
+    ///
+    /// The number of overlapping pairs.
+    ///
+    pub fn num_pairs(&mut self) -> usize {
+        self.refresh();
+        self.pairs.len()
+    }
+
+    pub fn find_colliding_pairs(&mut self, mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>)) {
+        let _ = self.try_find_colliding_pairs(|a, b| {
+            func(a, b);
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    ///
+    /// Like [`SweepAndPrune::find_colliding_pairs()`] except the search stops
+    /// as soon as the closure returns [`ControlFlow::Break`].
+    ///
+    pub fn try_find_colliding_pairs<B>(
+        &mut self,
+        mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        self.refresh();
+        for &(i, j) in self.pairs.iter() {
+            let (left, right) = self.bots.split_at_mut(j);
+            func(
+                AabbPin::from_mut(&mut left[i]),
+                AabbPin::from_mut(&mut right[0]),
+            )?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    pub fn find_all_not_in_rect<'b, K: Aabb<Num = T::Num>>(
+        &'b mut self,
+        mut rect: AabbPin<&mut K>,
+        mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
+    ) {
+        for b in AabbPin::from_mut(&mut *self.bots).iter_mut() {
+            if !rect.get().contains_rect(b.get()) {
+                closure(rect.borrow_mut(), b);
+            }
+        }
+    }
+
+    pub fn find_all_in_rect<'b, K: Aabb<Num = T::Num>>(
+        &'b mut self,
+        mut rect: AabbPin<&mut K>,
+        mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
+    ) {
+        let bots = self.candidates(rect.get(), IntersectionMode::Closed);
+        for b in bots {
+            if rect.get().contains_rect(b.get()) {
+                closure(rect.borrow_mut(), b);
+            }
+        }
+    }
+
+    pub fn find_all_intersect_rect<'b, K: Aabb<Num = T::Num>>(
+        &'b mut self,
+        mut rect: AabbPin<&mut K>,
+        mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
+    ) {
+        let mode = T::INTERSECTION_MODE.stricter(K::INTERSECTION_MODE);
+        let bots = self.candidates(rect.get(), mode);
+        for b in bots {
+            if mode.intersects_rect(rect.get(), b.get()) {
+                closure(rect.borrow_mut(), b);
+            }
+        }
+    }
+
+    fn refresh(&mut self) {
+        if self.stale {
+            self.update();
+        }
+    }
+
+    //The elements whose x range intersects the rect's, found by walking the
+    //sorted starts until they pass the rect, and returned in slice order.
+    fn candidates(
+        &mut self,
+        rect: &Rect<T::Num>,
+        mode: IntersectionMode,
+    ) -> impl Iterator<Item = AabbPin<&mut T>> {
+        self.refresh();
+
+        let bots = &*self.bots;
+        let mut found: Vec<_> = self.axes[0]
+            .iter()
+            .filter(|e| !e.is_end)
+            .take_while(|e| !mode.is_before(rect.x.end, e.value))
+            .filter(|e| !mode.is_before(bots[e.index].get().x.end, rect.x.start))
+            .map(|e| e.index)
+            .collect();
+        found.sort_unstable();
+
+        //Split each candidate off the front of what is left of the slice.
+        let mut rest = Some(AabbPin::from_mut(&mut *self.bots));
+        let mut offset = 0;
+        found.into_iter().map(move |index| {
+            let (_, tail) = rest.take().unwrap().split_at_mut(index - offset);
+            let (a, tail) = tail.split_first_mut().unwrap();
+            rest = Some(tail);
+            offset = index + 1;
+            a
+        })
+    }
+}
+
+#[inline(always)]
+fn ordered(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn insertion_sort<T: Aabb>(
+    bots: &[T],
+    list: &mut [Endpoint<T::Num>],
+    pairs: &mut BTreeSet<(usize, usize)>,
+) {
+    let mode = T::INTERSECTION_MODE;
+    for i in 1..list.len() {
+        let mut j = i;
+        while j > 0 && list[j].is_before(&list[j - 1], mode) {
+            let (a, b) = (list[j], list[j - 1]);
+            if a.index != b.index {
+                match (a.is_end, b.is_end) {
+                    //Started overlapping along this axis.
+                    (false, true)
+                        if mode.intersects_rect(bots[a.index].get(), bots[b.index].get()) =>
+                    {
+                        pairs.insert(ordered(a.index, b.index));
+                    }
+                    //Stopped overlapping along this axis.
+                    (true, false) => {
+                        pairs.remove(&ordered(a.index, b.index));
+                    }
+                    _ => {}
+                }
+            }
+            list.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
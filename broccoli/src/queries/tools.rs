@@ -30,35 +30,50 @@ pub fn get_section_mut<'a, I: Aabb, A: Axis>(
     arr: AabbPin<&'a mut [I]>,
     range: &Range<I::Num>,
 ) -> AabbPin<&'a mut [I]> {
-    let mut ii = arr.iter().enumerate();
+    get_section_mut_mode(axis, arr, range, I::INTERSECTION_MODE)
+}
 
-    let ii = &mut ii;
+#[inline(always)]
+pub fn get_section_mut_mode<'a, I: Aabb, A: Axis>(
+    axis: A,
+    arr: AabbPin<&'a mut [I]>,
+    range: &Range<I::Num>,
+    mode: IntersectionMode,
+) -> AabbPin<&'a mut [I]> {
+    let section = section_bounds(axis, &arr, range, mode);
+    arr.truncate(section)
+}
 
-    let start = {
-        let mut ii = ii.skip_while(|(_, i)| {
-            let rr = i.range(axis);
-            rr.end < range.start
-        });
+#[inline(always)]
+pub fn get_section<'a, I: Aabb, A: Axis>(
+    axis: A,
+    arr: &'a [I],
+    range: &Range<I::Num>,
+    mode: IntersectionMode,
+) -> &'a [I] {
+    &arr[section_bounds(axis, arr, range, mode)]
+}
 
-        if let Some(start) = ii.next() {
-            start.0
-        } else {
-            return AabbPin::new(&mut []);
-        }
+//The elements are sorted by the start of their range along the axis.
+#[inline(always)]
+fn section_bounds<I: Aabb, A: Axis>(
+    axis: A,
+    arr: &[I],
+    range: &Range<I::Num>,
+    mode: IntersectionMode,
+) -> core::ops::Range<usize> {
+    let start = match arr
+        .iter()
+        .position(|i| !mode.is_before(i.range(axis).end, range.start))
+    {
+        Some(start) => start,
+        None => return arr.len()..arr.len(),
     };
 
-    let end = {
-        let mut ii = ii.skip_while(|(_, i)| {
-            let rr = i.range(axis);
-            rr.start <= range.end
-        });
-
-        if let Some((end, _)) = ii.next() {
-            end
-        } else {
-            return arr.truncate_from(start..);
-        }
-    };
+    let end = arr[start + 1..]
+        .iter()
+        .position(|i| mode.is_before(range.end, i.range(axis).start))
+        .map_or(arr.len(), |end| start + 1 + end);
 
-    arr.truncate(start..end)
+    start..end
 }
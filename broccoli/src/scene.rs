@@ -0,0 +1,112 @@
+//!
+//! Keep the tree of elements that never move, such as level geometry, across frames
+//! and only build a tree of the elements that move every frame.
+//!
+//! ```rust
+//! use broccoli::{rect, scene::Scene};
+//!
+//! //Two walls that overlap each other.
+//! let mut scene = Scene::new(vec![rect(0, 100, 0, 10), rect(90, 100, 0, 100)]);
+//!
+//! for frame in 0..3 {
+//!     let mut bodies = [rect(10, 20, 5, 15), rect(15 + frame * 40, 25 + frame * 40, 5, 15)];
+//!
+//!     let (mut dynamic, mut with_static) = (0, 0);
+//!     scene.find_colliding_pairs(
+//!         &mut bodies,
+//!         |_, _| dynamic += 1,
+//!         |_, _| with_static += 1,
+//!     );
+//!
+//!     assert_eq!(dynamic, if frame == 0 { 1 } else { 0 });
+//!     assert_eq!(with_static, if frame == 2 { 3 } else { 2 });
+//! }
+//! ```
+//!
+
+use super::*;
+use queries::intersect_with::{DualVisitor, NodeBounds};
+
+///
+/// A tree of static elements that is built once, along with what is needed to find
+/// the pairs between them and a tree of dynamic elements built every frame.
+///
+/// Pairs of two static elements are never reported.
+///
+pub struct Scene<S: Aabb> {
+    statics: Vec<S>,
+    data: TreeData<S::Num>,
+    bounds: Vec<NodeBounds<S::Num>>,
+}
+
+impl<S: Aabb + ManySwap> Scene<S> {
+    ///
+    /// Build the static tree. The elements are kept in the order of the tree.
+    ///
+    pub fn new(mut statics: Vec<S>) -> Self {
+        let tree = Tree::new(&mut statics);
+        let data = tree.get_tree_data();
+        let bounds = tree.node_bounds();
+        Scene {
+            statics,
+            data,
+            bounds,
+        }
+    }
+}
+
+impl<S: Aabb> Scene<S> {
+    ///
+    /// The static elements in the order of the tree.
+    ///
+    #[must_use]
+    pub fn statics(&self) -> &[S] {
+        &self.statics
+    }
+
+    ///
+    /// The static tree. Only the inner data of the elements can be changed through it.
+    ///
+    pub fn static_tree(&mut self) -> Tree<'_, S> {
+        Tree::from_tree_data(&mut self.statics, &self.data)
+    }
+
+    ///
+    /// Return the static elements, dropping the tree.
+    ///
+    pub fn into_statics(self) -> Vec<S> {
+        self.statics
+    }
+
+    ///
+    /// Build a tree of the dynamic elements and find the pairs between them with
+    /// [`Tree::find_colliding_pairs()`], then find the pairs between them and the
+    /// static elements by recursing the dynamic tree and the static tree at the same time.
+    ///
+    pub fn find_colliding_pairs<D: Aabb<Num = S::Num> + ManySwap>(
+        &mut self,
+        dynamics: &mut [D],
+        dynamic: impl FnMut(AabbPin<&mut D>, AabbPin<&mut D>),
+        with_static: impl FnMut(AabbPin<&mut D>, AabbPin<&mut S>),
+    ) {
+        let mut tree = Tree::new(dynamics);
+        self.find_colliding_pairs_tree(&mut tree, dynamic, with_static);
+    }
+
+    ///
+    /// Like [`Scene::find_colliding_pairs()`] except with a dynamic tree that has already been built.
+    ///
+    pub fn find_colliding_pairs_tree<D: Aabb<Num = S::Num>>(
+        &mut self,
+        tree: &mut Tree<D>,
+        dynamic: impl FnMut(AabbPin<&mut D>, AabbPin<&mut D>),
+        mut with_static: impl FnMut(AabbPin<&mut D>, AabbPin<&mut S>),
+    ) {
+        tree.find_colliding_pairs(dynamic);
+
+        let bounds = tree.node_bounds();
+        let mut statics = Tree::from_tree_data(&mut self.statics, &self.data);
+        DualVisitor::new(tree.vistr_mut(), &bounds, statics.vistr_mut(), &self.bounds)
+            .recurse_seq(&mut with_static);
+    }
+}
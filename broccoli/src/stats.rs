@@ -0,0 +1,112 @@
+//!
+//! Statistics about the shape of a tree
+//!
+
+use super::*;
+use compt::Visitor;
+use num_traits::ToPrimitive;
+
+///
+/// The numbers behind how well the elements are spread out over a tree.
+/// Returned by [`Tree::stats()`]
+///
+#[derive(Clone, Debug)]
+pub struct TreeStats {
+    /// The number of elements in each level, starting with the root.
+    pub level_elems: Vec<usize>,
+    /// The number of elements of each leaf, in dfs preorder.
+    pub leaf_sizes: Vec<usize>,
+    /// The number of elements of each non leaf node, in dfs preorder.
+    pub internal_sizes: Vec<usize>,
+    /// The number of non leaf nodes without a divider.
+    /// Such a node and every node below it is empty.
+    pub num_empty_nodes: usize,
+    /// The widest cont of a node that has elements.
+    pub max_cont_width: f64,
+    /// The mean width of the conts of nodes that have elements.
+    pub mean_cont_width: f64,
+    /// An upper bound on the number of pairs of elements that [`Tree::find_colliding_pairs()`]
+    /// may check against each other. Every element of a node is counted against every other
+    /// element of the same node and every element of the nodes below it.
+    pub colfind_cost: usize,
+}
+
+impl TreeStats {
+    #[must_use]
+    pub fn num_levels(&self) -> usize {
+        self.level_elems.len()
+    }
+
+    #[must_use]
+    pub fn num_elems(&self) -> usize {
+        self.level_elems.iter().sum()
+    }
+}
+
+impl<'a, T: Aabb> Tree<'a, T>
+where
+    T::Num: num_traits::ToPrimitive,
+{
+    ///
+    /// Gather statistics about how the elements are distributed in this tree.
+    /// Useful for picking a tree height. See [`num_level`].
+    ///
+    #[must_use]
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats {
+            level_elems: vec![0; self.num_levels()],
+            leaf_sizes: Vec::new(),
+            internal_sizes: Vec::new(),
+            num_empty_nodes: 0,
+            max_cont_width: 0.0,
+            mean_cont_width: 0.0,
+            colfind_cost: 0,
+        };
+
+        let mut num_conts = 0;
+        recurse(self.vistr(), 0, &mut stats, &mut num_conts);
+
+        if num_conts > 0 {
+            stats.mean_cont_width /= num_conts as f64;
+        }
+        stats
+    }
+}
+
+//Returns the number of elements in this node and the nodes below it.
+fn recurse<T: Aabb>(
+    v: Vistr<Node<T, T::Num>>,
+    depth: usize,
+    stats: &mut TreeStats,
+    num_conts: &mut usize,
+) -> usize
+where
+    T::Num: num_traits::ToPrimitive,
+{
+    let (nn, rest) = v.next();
+    let num = nn.range.len();
+
+    stats.level_elems[depth] += num;
+
+    if num > 0 {
+        let width = (nn.cont.end.to_f64().unwrap() - nn.cont.start.to_f64().unwrap()).max(0.0);
+        stats.max_cont_width = stats.max_cont_width.max(width);
+        stats.mean_cont_width += width;
+        *num_conts += 1;
+    }
+
+    let below = if let Some([left, right]) = rest {
+        stats.internal_sizes.push(num);
+        if nn.div.is_none() {
+            stats.num_empty_nodes += 1;
+        }
+        recurse(left, depth + 1, stats, num_conts) + recurse(right, depth + 1, stats, num_conts)
+    } else {
+        stats.leaf_sizes.push(num);
+        0
+    };
+
+    stats.colfind_cost += num * num.saturating_sub(1) / 2 + num * below;
+
+    num + below
+}
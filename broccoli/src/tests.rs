@@ -50,3 +50,22 @@ fn test() {
         }
     }
 }
+
+#[test]
+fn test_try_from_malformed_tree_data() {
+    let mut bots: Vec<_> = (0..1000isize).map(|i| rect(i, i + 5, i, i + 5)).collect();
+
+    let mut data = Tree::new(&mut bots).get_tree_data();
+    let num_nodes = data.nodes.len();
+    assert!(num_nodes > 1);
+
+    let last = data.nodes.pop().unwrap();
+    data.nodes[0].range += last.range;
+
+    assert_eq!(
+        Tree::try_from_tree_data(&mut bots, &data).err(),
+        Some(TreeError::MalformedTreeData {
+            num_nodes: num_nodes - 1
+        })
+    );
+}
@@ -0,0 +1,165 @@
+//! Provides 3d broadphase collision detection.
+
+use super::*;
+
+impl<'a, T: Aabb3> Tree3<'a, T> {
+    pub fn find_colliding_pairs(&mut self, mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>)) {
+        recurse(self.vistr_mut(), &mut func);
+    }
+}
+
+fn recurse<T: Aabb3, F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>)>(
+    m: VistrMutPin<Node3<T, T::Num>>,
+    func: &mut F,
+) {
+    let (mut nn, rest) = m.next();
+    let axis = nn.axis;
+    let div = nn.div;
+    let cont = nn.cont;
+
+    sweep(axis.next(), nn.borrow_mut().into_range(), func);
+
+    if let Some([mut left, mut right]) = rest {
+        if div.is_some() {
+            let mut range = nn.into_range();
+            if !range.is_empty() {
+                let anchor = Anchor { axis, cont: &cont };
+                anchor.recurse(range.borrow_mut(), left.borrow_mut(), true, func);
+                anchor.recurse(range, right.borrow_mut(), false, func);
+            }
+        }
+        recurse(left, func);
+        recurse(right, func);
+    }
+}
+
+//Find pairs among elements that are sorted along the specified axis.
+fn sweep<T: Aabb3>(
+    axis: Axis3,
+    mut bots: AabbPin<&mut [T]>,
+    func: &mut impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>),
+) {
+    loop {
+        let temp = bots;
+        match temp.split_first_mut() {
+            Some((mut a, mut rest)) => {
+                for b in rest.borrow_mut() {
+                    //Exploit the sorted property, to exit early
+                    if b.range(axis).start > a.range(axis).end {
+                        break;
+                    }
+                    if a.get().intersects_rect(b.get()) {
+                        func(a.borrow_mut(), b);
+                    }
+                }
+                bots = rest;
+            }
+            None => break,
+        }
+    }
+}
+
+//A node whose elements are checked against the elements of its descendants.
+struct Anchor<'a, N> {
+    axis: Axis3,
+    cont: &'a Range<N>,
+}
+
+impl<'a, N: Num> Anchor<'a, N> {
+    fn recurse<T: Aabb3<Num = N>, F: FnMut(AabbPin<&mut T>, AabbPin<&mut T>)>(
+        &self,
+        mut anchor: AabbPin<&mut [T]>,
+        m: VistrMutPin<Node3<T, N>>,
+        is_left: bool,
+        func: &mut F,
+    ) {
+        let (nn, rest) = m.next();
+        let this_axis = nn.axis;
+        let div = nn.div;
+
+        //The anchor elements are sorted along this axis.
+        let sort_axis = self.axis.next();
+
+        for mut y in nn.into_range() {
+            //Every anchor element is within the anchor's cont.
+            if !y.range(self.axis).intersects(self.cont) {
+                continue;
+            }
+
+            for a in get_section_mut(sort_axis, anchor.borrow_mut(), y.range(sort_axis)) {
+                if a.get().intersects_rect(y.get()) {
+                    func(a, y.borrow_mut());
+                }
+            }
+        }
+
+        if let Some([left, right]) = rest {
+            let Some(div) = div else {
+                return;
+            };
+
+            if this_axis == self.axis {
+                if is_left && div < self.cont.start {
+                    self.recurse(anchor, right, is_left, func);
+                    return;
+                }
+                if !is_left && div >= self.cont.end {
+                    self.recurse(anchor, left, is_left, func);
+                    return;
+                }
+            }
+
+            self.recurse(anchor.borrow_mut(), left, is_left, func);
+            self.recurse(anchor, right, is_left, func);
+        }
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb3> Naive3<'a, T> {
+        pub fn find_colliding_pairs(
+            &mut self,
+            mut func: impl FnMut(AabbPin<&mut T>, AabbPin<&mut T>),
+        ) {
+            queries::for_every_pair(self.inner.borrow_mut(), move |a, b| {
+                if a.get().intersects_rect(b.get()) {
+                    func(a, b);
+                }
+            });
+        }
+    }
+
+    impl<'a, T: Aabb3> Assert3<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_query(&mut self) {
+            fn add_pair(res: &mut Vec<(usize, usize)>, a: usize, b: usize) {
+                res.push(if a < b { (a, b) } else { (b, a) });
+            }
+
+            let mut bots: Vec<_> = self
+                .inner
+                .iter()
+                .enumerate()
+                .map(|(i, x)| ManySwappable((*x.get(), i)))
+                .collect();
+
+            let mut naive_res = Vec::new();
+            Naive3::new(&mut bots).find_colliding_pairs(|a, b| {
+                add_pair(&mut naive_res, a.0 .1, b.0 .1);
+            });
+
+            let mut tree_res = Vec::new();
+            Tree3::new(&mut bots).find_colliding_pairs(|a, b| {
+                add_pair(&mut tree_res, a.0 .1, b.0 .1);
+            });
+
+            naive_res.sort_unstable();
+            tree_res.sort_unstable();
+
+            assert_eq!(naive_res.len(), tree_res.len());
+            assert_eq!(naive_res, tree_res);
+        }
+    }
+}
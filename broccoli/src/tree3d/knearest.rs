@@ -0,0 +1,346 @@
+//! 3d knearest query module
+
+use super::*;
+
+///The geometric functions that the user must provide.
+pub trait Knearest3<T: Aabb3> {
+    ///User define distance function from a point to an axis aligned plane of infinite size.
+    fn distance_to_aaplane(&mut self, point: Vec3<T::Num>, axis: Axis3, val: T::Num) -> T::Num;
+
+    ///User defined inexpensive distance function that that can be overly conservative.
+    ///It may be that the precise distance function is fast enough, in which case you can simply
+    ///return None. If None is desired, every call to this function for a particular element must
+    ///always return None.
+    fn distance_to_broad(&mut self, point: Vec3<T::Num>, a: AabbPin<&mut T>) -> Option<T::Num>;
+
+    ///User defined expensive distance function. Here the user can return fine-grained distance
+    ///of the shape contained in T instead of its bounding box.
+    fn distance_to_fine(&mut self, point: Vec3<T::Num>, a: AabbPin<&mut T>) -> T::Num;
+}
+
+///
+/// Find nearest using just axis alined bounding boxes. No fine-grained.
+///
+pub struct AabbKnearest3;
+
+impl<T: Aabb3> Knearest3<T> for AabbKnearest3
+where
+    T::Num: num_traits::Signed + num_traits::Zero,
+{
+    fn distance_to_aaplane(&mut self, point: Vec3<T::Num>, axis: Axis3, a: T::Num) -> T::Num {
+        use num_traits::Signed;
+        let d = (*point.get_axis(axis) - a).abs();
+        d * d
+    }
+
+    fn distance_to_broad(
+        &mut self,
+        _point: Vec3<T::Num>,
+        _rect: AabbPin<&mut T>,
+    ) -> Option<T::Num> {
+        None
+    }
+
+    fn distance_to_fine(&mut self, point: Vec3<T::Num>, a: AabbPin<&mut T>) -> T::Num {
+        use num_traits::Zero;
+
+        a.get()
+            .distance_squared_to_point(point)
+            .unwrap_or_else(T::Num::zero)
+    }
+}
+
+impl<T: Aabb3, K: Knearest3<T>> Knearest3<T> for &mut K {
+    fn distance_to_aaplane(&mut self, point: Vec3<T::Num>, axis: Axis3, val: T::Num) -> T::Num {
+        (*self).distance_to_aaplane(point, axis, val)
+    }
+
+    fn distance_to_broad(&mut self, point: Vec3<T::Num>, rect: AabbPin<&mut T>) -> Option<T::Num> {
+        (*self).distance_to_broad(point, rect)
+    }
+
+    fn distance_to_fine(&mut self, point: Vec3<T::Num>, bot: AabbPin<&mut T>) -> T::Num {
+        (*self).distance_to_fine(point, bot)
+    }
+}
+
+impl<'a, T: Aabb3> Tree3<'a, T> {
+    pub fn find_knearest(
+        &mut self,
+        point: Vec3<T::Num>,
+        num: usize,
+        mut ktrait: impl Knearest3<T>,
+    ) -> KResult3<'_, T> {
+        let mut rec = Recurser {
+            knear: &mut ktrait,
+            point,
+            closest: ClosestCand::new(num),
+        };
+
+        rec.recc(self.vistr_mut());
+
+        let num_entries = rec.closest.curr_num;
+        KResult3 {
+            num_entries,
+            inner: rec.closest.into_sorted(),
+        }
+    }
+}
+
+/// Returned by [`Tree3::find_knearest()`]
+#[derive(Debug)]
+pub struct KnearestResult3<'a, T: Aabb3> {
+    pub bot: AabbPin<&'a mut T>,
+    pub mag: T::Num,
+}
+
+struct ClosestCand<'a, T: Aabb3> {
+    //Can have multiple bots with the same mag. So the length could be bigger than num.
+    bots: Vec<KnearestResult3<'a, T>>,
+    //The current number of different distances in the vec
+    curr_num: usize,
+    //The max number of different distances.
+    num: usize,
+}
+impl<'a, T: Aabb3> ClosestCand<'a, T> {
+    //First is the closest
+    fn into_sorted(self) -> Vec<KnearestResult3<'a, T>> {
+        self.bots
+    }
+    fn new(num: usize) -> ClosestCand<'a, T> {
+        ClosestCand {
+            bots: Vec::with_capacity(num),
+            num,
+            curr_num: 0,
+        }
+    }
+
+    fn consider<K: Knearest3<T>>(
+        &mut self,
+        point: &Vec3<T::Num>,
+        knear: &mut K,
+        mut curr_bot: AabbPin<&'a mut T>,
+    ) {
+        if let Some(long_dis) = knear.distance_to_broad(*point, curr_bot.borrow_mut()) {
+            if self.curr_num == self.num {
+                if let Some(l) = self.bots.last() {
+                    if long_dis > l.mag {
+                        return;
+                    }
+                }
+            }
+        }
+        let curr_dis = knear.distance_to_fine(*point, curr_bot.borrow_mut());
+
+        let arr = &mut self.bots;
+
+        //The closest bots are at the start.
+        let insert_index = arr.iter().position(|a| curr_dis <= a.mag);
+
+        match insert_index {
+            Some(i) => {
+                //If we find a bot at the same distance of another bot, add it to that group.
+                //Otherwise start a new group.
+                if curr_dis < arr[i].mag {
+                    self.curr_num += 1;
+                }
+                arr.insert(
+                    i,
+                    KnearestResult3 {
+                        bot: curr_bot,
+                        mag: curr_dis,
+                    },
+                );
+
+                //If we have too many groups, delete the group thats furthest away.
+                if self.curr_num > self.num {
+                    let last_mag = arr.last().unwrap().mag;
+                    self.curr_num -= 1;
+                    while let Some(k) = arr.last() {
+                        if k.mag == last_mag {
+                            arr.pop();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            None => {
+                //Check if we can just make a new group at the end.
+                if self.curr_num < self.num {
+                    self.curr_num += 1;
+                    arr.push(KnearestResult3 {
+                        bot: curr_bot,
+                        mag: curr_dis,
+                    });
+                }
+            }
+        }
+    }
+
+    fn full_and_max_distance(&self) -> Option<T::Num> {
+        if self.curr_num == self.num {
+            self.bots.last().map(|a| a.mag)
+        } else {
+            None
+        }
+    }
+}
+
+struct Recurser<'a, T: Aabb3, K: Knearest3<T>> {
+    knear: K,
+    point: Vec3<T::Num>,
+    closest: ClosestCand<'a, T>,
+}
+
+impl<'a, T: Aabb3, K: Knearest3<T>> Recurser<'a, T, K> {
+    fn should_recurse(&mut self, axis: Axis3, val: T::Num) -> bool {
+        if let Some(m) = self.closest.full_and_max_distance() {
+            let dis = self.knear.distance_to_aaplane(self.point, axis, val);
+            dis < m
+        } else {
+            true
+        }
+    }
+
+    fn recc<'b: 'a>(&mut self, stuff: VistrMutPin<'a, Node3<'b, T, T::Num>>) {
+        let (nn, rest) = stuff.next();
+        let axis = nn.axis;
+
+        let handle_node = match rest {
+            Some([left, right]) => {
+                let div = match nn.div {
+                    Some(b) => b,
+                    None => return,
+                };
+
+                //recurse first. more likely closest is in a child.
+                if *self.point.get_axis(axis) < div {
+                    self.recc(left);
+                    if self.should_recurse(axis, div) {
+                        self.recc(right);
+                    }
+                } else {
+                    self.recc(right);
+                    if self.should_recurse(axis, div) {
+                        self.recc(left);
+                    }
+                }
+
+                if !nn.range.is_empty() {
+                    //Determine if we should handle this node or not.
+                    match nn.cont.contains_ext(*self.point.get_axis(axis)) {
+                        core::cmp::Ordering::Less => self.should_recurse(axis, nn.cont.start),
+                        core::cmp::Ordering::Greater => self.should_recurse(axis, nn.cont.end),
+                        core::cmp::Ordering::Equal => true,
+                    }
+                } else {
+                    false
+                }
+            }
+            None => true,
+        };
+
+        if handle_node {
+            for bot in nn.into_range().iter_mut() {
+                self.closest.consider(&self.point, &mut self.knear, bot);
+            }
+        }
+    }
+}
+
+///Returned by [`Tree3::find_knearest()`]
+pub struct KResult3<'a, T: Aabb3> {
+    num_entries: usize,
+    inner: Vec<KnearestResult3<'a, T>>,
+}
+
+impl<'a, T: Aabb3> KResult3<'a, T> {
+    ///Iterators over each group of ties starting with the closest.
+    ///All the elements in one group have the same distance.
+    #[inline(always)]
+    pub fn iter(
+        &mut self,
+    ) -> impl core::iter::FusedIterator<Item = &mut [KnearestResult3<'a, T>]> + DoubleEndedIterator
+    {
+        use slice_group_by::GroupByMut;
+        self.inner.linear_group_by_mut(|a, b| a.mag == b.mag)
+    }
+
+    ///Return the underlying datastructure
+    #[inline(always)]
+    pub fn into_vec(self) -> Vec<KnearestResult3<'a, T>> {
+        self.inner
+    }
+
+    ///returns the total number of elements counting ties
+    #[inline(always)]
+    pub fn total_len(&self) -> usize {
+        self.inner.len()
+    }
+    ///Returns the number of unique distances
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.num_entries
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb3> Naive3<'a, T> {
+        pub fn find_knearest(
+            &mut self,
+            point: Vec3<T::Num>,
+            num: usize,
+            mut ktrait: impl Knearest3<T>,
+        ) -> KResult3<'_, T> {
+            let mut closest = ClosestCand::new(num);
+
+            for b in self.inner.borrow_mut().iter_mut() {
+                closest.consider(&point, &mut ktrait, b);
+            }
+
+            let num_entries = closest.curr_num;
+            KResult3 {
+                num_entries,
+                inner: closest.into_sorted(),
+            }
+        }
+    }
+
+    impl<'a, T: Aabb3 + ManySwap> Assert3<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_k_nearest_mut(
+            &mut self,
+            point: Vec3<T::Num>,
+            num: usize,
+            mut knear: impl Knearest3<T>,
+        ) {
+            let mut tree = Tree3::new(self.inner);
+            let mut res_dino: Vec<_> = tree
+                .find_knearest(point, num, &mut knear)
+                .into_vec()
+                .into_iter()
+                .map(|a| (crate::assert::into_ptr_usize(a.bot), a.mag))
+                .collect();
+
+            let mut res_naive: Vec<_> = Naive3::new(self.inner)
+                .find_knearest(point, num, knear)
+                .into_vec()
+                .into_iter()
+                .map(|a| (crate::assert::into_ptr_usize(a.bot), a.mag))
+                .collect();
+
+            res_naive.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            res_dino.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+        }
+    }
+}
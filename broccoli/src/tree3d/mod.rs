@@ -0,0 +1,537 @@
+//!
+//! A 3d broccoli tree for axis aligned bounding boxes.
+//!
+//! It works the same way as [`Tree`](crate::Tree) except that
+//! the nodes cycle between dividing space along the x, y, and z axis.
+//! The elements of a node are sorted along the axis that comes after
+//! the one the node divides along.
+//!
+//! ```rust
+//! use broccoli::tree3d::{rect3, Tree3};
+//!
+//! let mut bots = [
+//!     (rect3(0, 10, 0, 10, 0, 10), 0usize),
+//!     (rect3(5, 15, 5, 15, 5, 15), 1),
+//!     (rect3(5, 15, 5, 15, 20, 30), 2),
+//! ];
+//!
+//! let mut tree = Tree3::new(&mut bots);
+//!
+//! let mut pairs = vec![];
+//! tree.find_colliding_pairs(|a, b| pairs.push((a.1, b.1)));
+//! assert_eq!(pairs.len(), 1);
+//! ```
+//!
+
+use super::*;
+use compt::Visitor;
+
+pub mod colfind;
+pub mod knearest;
+pub mod raycast;
+pub mod rect;
+
+use crate::assert::Assert3;
+use crate::assert::Naive3;
+
+///
+/// One of the three axes of 3d space.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis3 {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis3 {
+    ///
+    /// The axis that the children of a node dividing along this axis divide along.
+    ///
+    #[inline(always)]
+    #[must_use]
+    pub const fn next(self) -> Axis3 {
+        match self {
+            Axis3::X => Axis3::Y,
+            Axis3::Y => Axis3::Z,
+            Axis3::Z => Axis3::X,
+        }
+    }
+}
+
+///
+/// A point or direction in 3d space.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vec3<N> {
+    pub x: N,
+    pub y: N,
+    pub z: N,
+}
+
+///Shorthand constructor of [`Vec3`]
+#[inline(always)]
+#[must_use]
+pub const fn vec3<N>(x: N, y: N, z: N) -> Vec3<N> {
+    Vec3 { x, y, z }
+}
+
+impl<N> Vec3<N> {
+    #[inline(always)]
+    pub fn get_axis(&self, axis: Axis3) -> &N {
+        match axis {
+            Axis3::X => &self.x,
+            Axis3::Y => &self.y,
+            Axis3::Z => &self.z,
+        }
+    }
+}
+
+///
+/// An axis aligned box in 3d space.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect3<N> {
+    pub x: Range<N>,
+    pub y: Range<N>,
+    pub z: Range<N>,
+}
+
+///Shorthand constructor of [`Rect3`]
+#[inline(always)]
+#[must_use]
+pub fn rect3<N>(xstart: N, xend: N, ystart: N, yend: N, zstart: N, zend: N) -> Rect3<N> {
+    Rect3::new(xstart, xend, ystart, yend, zstart, zend)
+}
+
+impl<N> Rect3<N> {
+    #[inline(always)]
+    #[must_use]
+    pub fn new(xstart: N, xend: N, ystart: N, yend: N, zstart: N, zend: N) -> Rect3<N> {
+        Rect3 {
+            x: Range::new(xstart, xend),
+            y: Range::new(ystart, yend),
+            z: Range::new(zstart, zend),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_range(&self, axis: Axis3) -> &Range<N> {
+        match axis {
+            Axis3::X => &self.x,
+            Axis3::Y => &self.y,
+            Axis3::Z => &self.z,
+        }
+    }
+}
+
+impl<N: Copy + PartialOrd> Rect3<N> {
+    #[must_use]
+    pub fn contains_point(&self, point: Vec3<N>) -> bool {
+        self.x.contains(point.x) && self.y.contains(point.y) && self.z.contains(point.z)
+    }
+
+    #[must_use]
+    pub fn contains_rect(&self, other: &Rect3<N>) -> bool {
+        self.x.contains_range(&other.x)
+            && self.y.contains_range(&other.y)
+            && self.z.contains_range(&other.z)
+    }
+
+    #[must_use]
+    pub fn intersects_rect(&self, other: &Rect3<N>) -> bool {
+        self.x.intersects(&other.x) && self.y.intersects(&other.y) && self.z.intersects(&other.z)
+    }
+}
+
+impl<N> Rect3<N>
+where
+    N: Copy
+        + PartialOrd
+        + core::ops::Sub<Output = N>
+        + core::ops::Mul<Output = N>
+        + core::ops::Add<Output = N>,
+{
+    ///
+    /// The squared distance from the point to the closest point of the box,
+    /// or None if the point is inside the box.
+    ///
+    #[must_use]
+    pub fn distance_squared_to_point(&self, point: Vec3<N>) -> Option<N> {
+        fn dis<N: Copy + PartialOrd + core::ops::Sub<Output = N>>(r: &Range<N>, a: N) -> Option<N> {
+            if a < r.start {
+                Some(r.start - a)
+            } else if a > r.end {
+                Some(a - r.end)
+            } else {
+                None
+            }
+        }
+
+        [
+            dis(&self.x, point.x),
+            dis(&self.y, point.y),
+            dis(&self.z, point.z),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|a| a * a)
+        .reduce(|a, b| a + b)
+    }
+}
+
+///
+/// Trait to signify that this object has a 3d axis aligned bounding box.
+///
+pub trait Aabb3 {
+    type Num: Num;
+    fn get(&self) -> &Rect3<Self::Num>;
+}
+
+pub(crate) trait Aabb3Ext: Aabb3 {
+    #[inline(always)]
+    fn range(&self, a: Axis3) -> &Range<Self::Num> {
+        self.get().get_range(a)
+    }
+}
+impl<T: Aabb3> Aabb3Ext for T {}
+
+/// Like [`HasInner`] except for elements with a 3d bounding box.
+pub trait HasInner3: Aabb3 {
+    type Inner;
+
+    #[inline(always)]
+    fn get_inner_mut(&mut self) -> &mut Self::Inner {
+        self.destruct_mut().1
+    }
+
+    fn destruct_mut(&mut self) -> (&Rect3<Self::Num>, &mut Self::Inner);
+}
+
+impl<N: Num> Aabb3 for Rect3<N> {
+    type Num = N;
+    #[inline(always)]
+    fn get(&self) -> &Rect3<Self::Num> {
+        self
+    }
+}
+
+impl<N: Num, T> Aabb3 for (Rect3<N>, T) {
+    type Num = N;
+    #[inline(always)]
+    fn get(&self) -> &Rect3<Self::Num> {
+        &self.0
+    }
+}
+
+impl<N: Num, T> HasInner3 for (Rect3<N>, T) {
+    type Inner = T;
+    #[inline(always)]
+    fn destruct_mut(&mut self) -> (&Rect3<Self::Num>, &mut Self::Inner) {
+        (&self.0, &mut self.1)
+    }
+}
+
+impl<N: Num, T> Aabb3 for &mut (Rect3<N>, T) {
+    type Num = N;
+    #[inline(always)]
+    fn get(&self) -> &Rect3<Self::Num> {
+        &self.0
+    }
+}
+
+impl<N: Num, T> HasInner3 for &mut (Rect3<N>, T) {
+    type Inner = T;
+    #[inline(always)]
+    fn destruct_mut(&mut self) -> (&Rect3<Self::Num>, &mut Self::Inner) {
+        (&self.0, &mut self.1)
+    }
+}
+
+impl<T: Aabb3> Aabb3 for ManySwappable<T> {
+    type Num = T::Num;
+    #[inline(always)]
+    fn get(&self) -> &Rect3<Self::Num> {
+        self.0.get()
+    }
+}
+
+impl<T: HasInner3> HasInner3 for ManySwappable<T> {
+    type Inner = T::Inner;
+    #[inline(always)]
+    fn destruct_mut(&mut self) -> (&Rect3<Self::Num>, &mut Self::Inner) {
+        self.0.destruct_mut()
+    }
+}
+
+impl<N> ManySwap for Rect3<N> {}
+impl<N> ManySwap for &mut Rect3<N> {}
+
+impl<N, T> ManySwap for &mut (Rect3<N>, T) {}
+
+impl<N, T> ManySwap for (Rect3<N>, &mut T) {}
+impl<N, T> ManySwap for (Rect3<N>, &T) {}
+
+impl<N> ManySwap for (Rect3<N>, ()) {}
+impl<N> ManySwap for (Rect3<N>, usize) {}
+impl<N> ManySwap for (Rect3<N>, u32) {}
+impl<N> ManySwap for (Rect3<N>, u64) {}
+
+///
+/// The node of a 3d broccoli tree.
+///
+pub struct Node3<'a, T, N> {
+    /// Sorted along the axis after [`Node3::axis`].
+    pub range: AabbPin<&'a mut [T]>,
+
+    /// if range is empty, then value is `[default,default]`.
+    /// if range is not empty, then cont is the min max bounds of the elements along [`Node3::axis`].
+    pub cont: axgeom::Range<N>,
+
+    /// for non leafs:
+    ///   if there is a bot either in this node or in a child node, then div is some.
+    ///
+    /// for leafs:
+    ///   value is none
+    pub div: Option<N>,
+
+    /// The axis that the divider partitions space along.
+    pub axis: Axis3,
+}
+
+impl<'a, T, N> Node3<'a, T, N> {
+    pub fn borrow_range(&mut self) -> AabbPin<&mut [T]> {
+        self.range.borrow_mut()
+    }
+}
+
+///
+/// The main primitive to build a 3d tree.
+///
+pub struct TreeBuildVisitor3<'a, T> {
+    bots: &'a mut [T],
+    current_height: usize,
+    axis: Axis3,
+}
+
+pub struct NodeBuildResult3<'a, T: Aabb3> {
+    pub node: Node3<'a, T, T::Num>,
+    pub rest: Option<[TreeBuildVisitor3<'a, T>; 2]>,
+}
+
+impl<'a, T: Aabb3 + ManySwap> TreeBuildVisitor3<'a, T> {
+    #[must_use]
+    pub fn new(num_levels: usize, bots: &'a mut [T]) -> TreeBuildVisitor3<'a, T> {
+        assert!(num_levels >= 1);
+        TreeBuildVisitor3 {
+            bots,
+            current_height: num_levels - 1,
+            axis: Axis3::X,
+        }
+    }
+
+    pub fn get_bots(&self) -> &[T] {
+        self.bots
+    }
+
+    #[must_use]
+    pub fn get_height(&self) -> usize {
+        self.current_height
+    }
+
+    #[must_use]
+    pub fn build_and_next(self) -> NodeBuildResult3<'a, T> {
+        let axis = self.axis;
+
+        //leaf case
+        if self.current_height == 0 {
+            let bots = self.bots;
+            let cont = create_cont(axis, bots);
+            sort(axis.next(), bots);
+            let node = Node3 {
+                range: AabbPin::new(bots),
+                cont,
+                div: None,
+                axis,
+            };
+            return NodeBuildResult3 { node, rest: None };
+        }
+
+        let (div, mid, left, right) = if self.bots.is_empty() {
+            (None, self.bots, &mut [][..], &mut [][..])
+        } else {
+            let bots = self.bots;
+            let med_index = bots.len() / 2;
+            let (_, med, _) = bots.select_nth_unstable_by(med_index, |a, b| cmp_start(axis, a, b));
+            let div = med.range(axis).start;
+
+            let (mid, rest) = partition_left(bots, |a| a.range(axis).contains(div));
+            let (left, right) = partition_left(rest, |a| a.range(axis).end < div);
+            (Some(div), mid, left, right)
+        };
+
+        let cont = create_cont(axis, mid);
+        sort(axis.next(), mid);
+
+        let node = Node3 {
+            range: AabbPin::new(mid),
+            cont,
+            div,
+            axis,
+        };
+
+        let current_height = self.current_height - 1;
+        NodeBuildResult3 {
+            node,
+            rest: Some([
+                TreeBuildVisitor3 {
+                    bots: left,
+                    current_height,
+                    axis: axis.next(),
+                },
+                TreeBuildVisitor3 {
+                    bots: right,
+                    current_height,
+                    axis: axis.next(),
+                },
+            ]),
+        }
+    }
+
+    pub fn recurse_seq(self, buffer: &mut Vec<Node3<'a, T, T::Num>>) {
+        let NodeBuildResult3 { node, rest } = self.build_and_next();
+        buffer.push(node);
+        if let Some([left, right]) = rest {
+            left.recurse_seq(buffer);
+            right.recurse_seq(buffer);
+        }
+    }
+}
+
+fn cmp_start<T: Aabb3>(axis: Axis3, a: &T, b: &T) -> core::cmp::Ordering {
+    if a.range(axis).start > b.range(axis).start {
+        core::cmp::Ordering::Greater
+    } else {
+        core::cmp::Ordering::Less
+    }
+}
+
+fn sort<T: Aabb3>(axis: Axis3, bots: &mut [T]) {
+    bots.sort_unstable_by(|a, b| cmp_start(axis, a, b));
+}
+
+fn create_cont<T: Aabb3>(axis: Axis3, bots: &[T]) -> axgeom::Range<T::Num> {
+    let Some((first, rest)) = bots.split_first() else {
+        return Default::default();
+    };
+
+    let mut cont = *first.range(axis);
+    for a in rest {
+        cont.grow_to_fit(a.range(axis));
+    }
+    cont
+}
+
+fn partition_left<T>(arr: &mut [T], mut func: impl FnMut(&T) -> bool) -> (&mut [T], &mut [T]) {
+    let mut m = 0;
+    for a in 0..arr.len() {
+        if func(&arr[a]) {
+            arr.swap(a, m);
+            m += 1;
+        }
+    }
+    arr.split_at_mut(m)
+}
+
+//this can have some false positives.
+//but it will still prune a lot of bots.
+fn get_section_mut<'a, T: Aabb3>(
+    axis: Axis3,
+    arr: AabbPin<&'a mut [T]>,
+    range: &Range<T::Num>,
+) -> AabbPin<&'a mut [T]> {
+    let start = arr
+        .iter()
+        .position(|a| a.range(axis).end >= range.start)
+        .unwrap_or(arr.len());
+
+    let end = arr[start..]
+        .iter()
+        .position(|a| a.range(axis).start > range.end)
+        .map(|a| start + a)
+        .unwrap_or(arr.len());
+
+    arr.truncate(start..end)
+}
+
+///
+/// A 3d broccoli Tree.
+///
+pub struct Tree3<'a, T: Aabb3> {
+    nodes: Box<[Node3<'a, T, T::Num>]>,
+}
+
+impl<'a, T: Aabb3 + 'a> Tree3<'a, T> {
+    ///
+    /// Create a new tree with the default tree height heuristic
+    ///
+    pub fn new(bots: &'a mut [T]) -> Self
+    where
+        T: ManySwap,
+    {
+        let num_levels = num_level::default(bots.len());
+        let mut buffer = Vec::with_capacity(num_level::num_nodes(num_levels));
+        TreeBuildVisitor3::new(num_levels, bots).recurse_seq(&mut buffer);
+        Tree3::from_nodes(buffer)
+    }
+
+    ///
+    /// User responsibility to provide a distribution that is a
+    /// valid broccoli tree.
+    ///
+    pub fn from_nodes(nodes: Vec<Node3<'a, T, T::Num>>) -> Self {
+        Tree3 {
+            nodes: nodes.into_boxed_slice(),
+        }
+    }
+
+    ///
+    /// Return the underlying data.
+    ///
+    pub fn into_nodes(self) -> Vec<Node3<'a, T, T::Num>> {
+        self.nodes.into_vec()
+    }
+
+    #[inline(always)]
+    pub fn vistr_mut(&mut self) -> VistrMutPin<'_, Node3<'a, T, T::Num>> {
+        let tree = compt::dfs_order::CompleteTreeMut::from_preorder_mut(&mut self.nodes).unwrap();
+        VistrMutPin::new(tree.vistr_mut())
+    }
+
+    #[inline(always)]
+    pub fn vistr(&self) -> Vistr<'_, Node3<'a, T, T::Num>> {
+        let tree = compt::dfs_order::CompleteTree::from_preorder(&self.nodes).unwrap();
+
+        tree.vistr()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn num_levels(&self) -> usize {
+        compt::dfs_order::CompleteTree::from_preorder(&self.nodes)
+            .unwrap()
+            .get_height()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    #[inline(always)]
+    pub fn get_nodes(&self) -> &[Node3<'a, T, T::Num>] {
+        &self.nodes
+    }
+}
@@ -0,0 +1,348 @@
+//! 3d raycast query module
+
+use super::*;
+use axgeom::CastResult;
+
+///
+/// A ray in 3d space.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Ray3<N> {
+    pub point: Vec3<N>,
+    pub dir: Vec3<N>,
+}
+
+impl<N: num_traits::Signed + Copy + PartialOrd> Ray3<N> {
+    ///
+    /// Return how many multiples of `dir` away from `point`
+    /// the ray hits the axis aligned plane.
+    ///
+    pub fn cast_to_aaplane(&self, axis: Axis3, val: N) -> CastResult<N> {
+        let pp = *self.point.get_axis(axis);
+        let vv = *self.dir.get_axis(axis);
+
+        if vv.is_zero() {
+            return CastResult::NoHit;
+        }
+
+        let t = (val - pp) / vv;
+        if t >= N::zero() {
+            CastResult::Hit(t)
+        } else {
+            CastResult::NoHit
+        }
+    }
+
+    ///
+    /// Return how many multiples of `dir` away from `point`
+    /// the ray hits the box.
+    ///
+    pub fn cast_to_rect(&self, rect: &Rect3<N>) -> CastResult<N> {
+        if rect.contains_point(self.point) {
+            return CastResult::Hit(N::zero());
+        }
+
+        let mut best: Option<N> = None;
+        for axis in [Axis3::X, Axis3::Y, Axis3::Z] {
+            let r = rect.get_range(axis);
+            for val in [r.start, r.end] {
+                if let CastResult::Hit(t) = self.cast_to_aaplane(axis, val) {
+                    let p = vec3(
+                        self.point.x + self.dir.x * t,
+                        self.point.y + self.dir.y * t,
+                        self.point.z + self.dir.z * t,
+                    );
+
+                    //The hit point is on the plane, so only check the other two axes.
+                    let a1 = axis.next();
+                    let a2 = a1.next();
+                    if rect.get_range(a1).contains(*p.get_axis(a1))
+                        && rect.get_range(a2).contains(*p.get_axis(a2))
+                    {
+                        best = Some(match best {
+                            Some(b) if b < t => b,
+                            _ => t,
+                        });
+                    }
+                }
+            }
+        }
+
+        match best {
+            Some(b) => CastResult::Hit(b),
+            None => CastResult::NoHit,
+        }
+    }
+}
+
+///This is the trait that defines raycast specific geometric functions that are needed by this raytracing algorithm.
+///By containing all these functions in this trait, we can keep the trait bounds of the underlying Num to a minimum
+///of only needing Ord.
+pub trait RayCast3<T: Aabb3> {
+    ///Return the cast result to a axis aligned plane of infinite size.
+    fn cast_to_aaplane(
+        &mut self,
+        ray: &Ray3<T::Num>,
+        axis: Axis3,
+        val: T::Num,
+    ) -> CastResult<T::Num>;
+
+    ///Return the cast result that is cheap and overly conservative.
+    ///It may be that the precise cast is fast enough, in which case you can simply
+    ///return None. If None is desired, every call to this function for a particular element must
+    ///always return None.
+    fn cast_broad(&mut self, ray: &Ray3<T::Num>, a: AabbPin<&mut T>) -> Option<CastResult<T::Num>>;
+
+    ///Return the exact cast result.
+    fn cast_fine(&mut self, ray: &Ray3<T::Num>, a: AabbPin<&mut T>) -> CastResult<T::Num>;
+}
+
+///
+/// No fine-grained just cast to aabb
+///
+pub struct AabbRaycast3;
+
+impl<T: Aabb3> RayCast3<T> for AabbRaycast3
+where
+    T::Num: num_traits::Signed,
+{
+    fn cast_to_aaplane(
+        &mut self,
+        ray: &Ray3<T::Num>,
+        axis: Axis3,
+        val: T::Num,
+    ) -> CastResult<T::Num> {
+        ray.cast_to_aaplane(axis, val)
+    }
+
+    fn cast_broad(
+        &mut self,
+        _ray: &Ray3<T::Num>,
+        _a: AabbPin<&mut T>,
+    ) -> Option<CastResult<T::Num>> {
+        None
+    }
+
+    fn cast_fine(&mut self, ray: &Ray3<T::Num>, a: AabbPin<&mut T>) -> CastResult<T::Num> {
+        ray.cast_to_rect(a.get())
+    }
+}
+
+impl<T: Aabb3, R: RayCast3<T>> RayCast3<T> for &mut R {
+    fn cast_to_aaplane(
+        &mut self,
+        ray: &Ray3<T::Num>,
+        axis: Axis3,
+        val: T::Num,
+    ) -> CastResult<T::Num> {
+        (*self).cast_to_aaplane(ray, axis, val)
+    }
+
+    fn cast_broad(&mut self, ray: &Ray3<T::Num>, a: AabbPin<&mut T>) -> Option<CastResult<T::Num>> {
+        (*self).cast_broad(ray, a)
+    }
+
+    fn cast_fine(&mut self, ray: &Ray3<T::Num>, a: AabbPin<&mut T>) -> CastResult<T::Num> {
+        (*self).cast_fine(ray, a)
+    }
+}
+
+///What is returned when the ray hits something.
+///It provides the length of the ray,
+///as well as all solutions in a unspecified order.
+pub struct CastAnswer3<'a, T: Aabb3> {
+    pub elems: Vec<AabbPin<&'a mut T>>,
+    pub mag: T::Num,
+}
+
+impl<'a, T: Aabb3> Tree3<'a, T> {
+    pub fn cast_ray<R: RayCast3<T>>(
+        &mut self,
+        ray: Ray3<T::Num>,
+        mut rtrait: R,
+    ) -> CastResult<CastAnswer3<'_, T>> {
+        let mut rec = Recurser {
+            rtrait: &mut rtrait,
+            ray,
+            closest: Closest { closest: None },
+        };
+        rec.recc(self.vistr_mut());
+
+        rec.closest.into_answer()
+    }
+}
+
+struct Recurser<'a, T: Aabb3, R: RayCast3<T>> {
+    rtrait: R,
+    ray: Ray3<T::Num>,
+    closest: Closest<'a, T>,
+}
+
+impl<'a, T: Aabb3, R: RayCast3<T>> Recurser<'a, T, R> {
+    fn should_recurse(&mut self, axis: Axis3, val: T::Num) -> bool {
+        match self.rtrait.cast_to_aaplane(&self.ray, axis, val) {
+            CastResult::Hit(val) => match self.closest.get_dis() {
+                Some(dis) => val <= dis,
+                None => true,
+            },
+            CastResult::NoHit => false,
+        }
+    }
+
+    fn recc<'b: 'a>(&mut self, stuff: VistrMutPin<'a, Node3<'b, T, T::Num>>) {
+        let (nn, rest) = stuff.next();
+        let axis = nn.axis;
+
+        let handle_curr = if let Some([left, right]) = rest {
+            let div = match nn.div {
+                Some(b) => b,
+                None => return,
+            };
+
+            //more likely to find closest in child than current node.
+            //so recurse first before handling this node.
+            if *self.ray.point.get_axis(axis) < div {
+                self.recc(left);
+                if self.should_recurse(axis, div) {
+                    self.recc(right);
+                }
+            } else {
+                self.recc(right);
+                if self.should_recurse(axis, div) {
+                    self.recc(left);
+                }
+            }
+
+            if !nn.range.is_empty() {
+                //Determine if we should handle this node or not.
+                match nn.cont.contains_ext(*self.ray.point.get_axis(axis)) {
+                    core::cmp::Ordering::Less => self.should_recurse(axis, nn.cont.start),
+                    core::cmp::Ordering::Greater => self.should_recurse(axis, nn.cont.end),
+                    core::cmp::Ordering::Equal => true,
+                }
+            } else {
+                false
+            }
+        } else {
+            true
+        };
+
+        if handle_curr {
+            for b in nn.into_range().iter_mut() {
+                self.closest.consider(&self.ray, b, &mut self.rtrait);
+            }
+        }
+    }
+}
+
+struct Closest<'a, T: Aabb3> {
+    closest: Option<(Vec<AabbPin<&'a mut T>>, T::Num)>,
+}
+impl<'a, T: Aabb3> Closest<'a, T> {
+    fn consider<R: RayCast3<T>>(
+        &mut self,
+        ray: &Ray3<T::Num>,
+        mut b: AabbPin<&'a mut T>,
+        raytrait: &mut R,
+    ) {
+        //first check if bounding box could possibly be a candidate.
+        if let Some(broad) = raytrait.cast_broad(ray, b.borrow_mut()) {
+            let y = match broad {
+                CastResult::Hit(val) => val,
+                CastResult::NoHit => return,
+            };
+
+            if let Some(dis) = self.get_dis() {
+                if y > dis {
+                    //no way this bot will be a candidate, return.
+                    return;
+                }
+            }
+        }
+
+        let x = match raytrait.cast_fine(ray, b.borrow_mut()) {
+            CastResult::Hit(val) => val,
+            CastResult::NoHit => return,
+        };
+
+        match self.closest.as_mut() {
+            Some(dis) => {
+                if x < dis.1 {
+                    dis.0.clear();
+                    dis.0.push(b);
+                    dis.1 = x;
+                } else if x == dis.1 {
+                    dis.0.push(b);
+                }
+            }
+            None => self.closest = Some((vec![b], x)),
+        };
+    }
+
+    fn get_dis(&self) -> Option<T::Num> {
+        self.closest.as_ref().map(|x| x.1)
+    }
+
+    fn into_answer(self) -> CastResult<CastAnswer3<'a, T>> {
+        match self.closest {
+            Some((elems, mag)) => CastResult::Hit(CastAnswer3 { elems, mag }),
+            None => CastResult::NoHit,
+        }
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb3> Naive3<'a, T> {
+        pub fn cast_ray<R: RayCast3<T>>(
+            &mut self,
+            ray: Ray3<T::Num>,
+            mut rtrait: R,
+        ) -> CastResult<CastAnswer3<'_, T>> {
+            let mut closest = Closest { closest: None };
+
+            for b in self.iter_mut() {
+                closest.consider(&ray, b, &mut rtrait);
+            }
+
+            closest.into_answer()
+        }
+    }
+
+    impl<'a, T: Aabb3 + ManySwap> Assert3<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_raycast(&mut self, ray: Ray3<T::Num>, mut rtrait: impl RayCast3<T>) {
+            fn into_vec<T: Aabb3>(res: CastResult<CastAnswer3<T>>) -> Vec<(usize, T::Num)> {
+                match res {
+                    CastResult::Hit(CastAnswer3 { elems, mag }) => elems
+                        .into_iter()
+                        .map(|a| (crate::assert::into_ptr_usize(a), mag))
+                        .collect(),
+                    CastResult::NoHit => Vec::new(),
+                }
+            }
+
+            let mut tree = Tree3::new(self.inner);
+            let mut res_dino = into_vec(tree.cast_ray(ray, &mut rtrait));
+
+            let mut res_naive = into_vec(Naive3::new(self.inner).cast_ray(ray, rtrait));
+
+            res_naive.sort_by_key(|a| a.0);
+            res_dino.sort_by_key(|a| a.0);
+
+            assert_eq!(
+                res_naive.len(),
+                res_dino.len(),
+                "len:{:?}",
+                (res_naive, res_dino)
+            );
+            assert!(
+                res_naive.iter().eq(res_dino.iter()),
+                "nop:\n\n naive:{:?} \n\n broc:{:?}",
+                res_naive,
+                res_dino
+            );
+        }
+    }
+}
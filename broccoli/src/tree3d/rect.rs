@@ -0,0 +1,90 @@
+//! 3d rect query module
+
+use super::*;
+
+impl<'a, T: Aabb3> Tree3<'a, T> {
+    pub fn find_all_intersect_rect<'b, K: Aabb3<Num = T::Num>>(
+        &'b mut self,
+        rect: AabbPin<&mut K>,
+        mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
+    ) {
+        rect_recurse(self.vistr_mut(), rect, &mut |r, a| {
+            if r.get().intersects_rect(a.get()) {
+                closure(r, a);
+            }
+        });
+    }
+}
+
+fn rect_recurse<
+    'a,
+    T: Aabb3,
+    F: FnMut(AabbPin<&mut K>, AabbPin<&'a mut T>),
+    K: Aabb3<Num = T::Num>,
+>(
+    m: VistrMutPin<'a, Node3<T, T::Num>>,
+    mut rect: AabbPin<&mut K>,
+    func: &mut F,
+) {
+    let (nn, rest) = m.next();
+    let axis = nn.axis;
+    let div = nn.div;
+
+    let sl = get_section_mut(axis.next(), nn.into_range(), rect.range(axis.next()));
+    for i in sl {
+        func(rect.borrow_mut(), i);
+    }
+
+    if let Some([left, right]) = rest {
+        let Some(div) = div else {
+            return;
+        };
+
+        if div >= rect.range(axis).start {
+            rect_recurse(left, rect.borrow_mut(), func);
+        }
+        if div <= rect.range(axis).end {
+            rect_recurse(right, rect, func);
+        }
+    }
+}
+
+mod assert {
+    use super::*;
+
+    impl<'a, T: Aabb3 + ManySwap> Assert3<'a, T> {
+        ///Panics if a disconnect is detected between tree and naive queries.
+        pub fn assert_rect(&mut self, mut rect: Rect3<T::Num>) {
+            let mut tree = Tree3::new(self.inner);
+            let mut res_dino = Vec::new();
+            tree.find_all_intersect_rect(AabbPin::new(&mut rect), |_, a| {
+                res_dino.push(crate::assert::into_ptr_usize(a));
+            });
+
+            let mut res_naive = Vec::new();
+            Naive3::new(self.inner).find_all_intersect_rect(AabbPin::new(&mut rect), |_, a| {
+                res_naive.push(crate::assert::into_ptr_usize(a));
+            });
+
+            res_dino.sort_unstable();
+            res_naive.sort_unstable();
+
+            assert_eq!(res_naive.len(), res_dino.len());
+            assert!(res_naive.iter().eq(res_dino.iter()));
+        }
+    }
+
+    impl<'a, T: Aabb3> Naive3<'a, T> {
+        pub fn find_all_intersect_rect<'b, K: Aabb3<Num = T::Num>>(
+            &'b mut self,
+            mut rect: AabbPin<&mut K>,
+            mut closure: impl FnMut(AabbPin<&mut K>, AabbPin<&'b mut T>),
+        ) {
+            for b in self.iter_mut() {
+                if rect.get().intersects_rect(b.get()) {
+                    closure(rect.borrow_mut(), b);
+                }
+            }
+        }
+    }
+}
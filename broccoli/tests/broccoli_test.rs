@@ -48,3 +48,411 @@ fn test1() {
         }
     }
 }
+
+#[test]
+fn test_refit() {
+    use broccoli::aabb::ManySwappable;
+    use broccoli::assert::Naive;
+    use broccoli::build::{CoherentSorter, TreeEmbryo};
+
+    #[derive(Copy, Clone)]
+    enum Refit {
+        Plain,
+        Coherent,
+        //The bots are put back in the order they were made in before every frame.
+        PreviousOrder,
+    }
+
+    fn check(num_bots: usize, refit: Refit) {
+        let s = dists::spiral_iter([400.0, 400.0], 4.0, 1.0);
+
+        let mut bots: Vec<_> = s
+            .take(num_bots)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                let r =
+                    axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8));
+                ManySwappable((r, i))
+            })
+            .collect();
+
+        //The bots are far from sorted here.
+        let tree = broccoli::Tree::new_with_sorter(&mut bots, CoherentSorter);
+        broccoli::assert::assert_tree_invariants(&tree);
+        let mut data = tree.get_tree_data();
+
+        for frame in 0..4 {
+            let order: Vec<_> = bots.iter().map(|b| (b.0).1).collect();
+            if let Refit::PreviousOrder = refit {
+                bots.sort_unstable_by_key(|b| (b.0).1);
+            }
+
+            //Move some bots a lot so that they cross dividers.
+            for (i, b) in bots.iter_mut().enumerate() {
+                let k = if i % 7 == 0 { 50 } else { (i % 3) as i64 };
+                let r = &mut (b.0).0;
+                let d = if frame % 2 == 0 { k } else { -k };
+                r.x.start += d;
+                r.x.end += d;
+                r.y.start -= d;
+                r.y.end -= d;
+            }
+
+            let mut tree = match refit {
+                Refit::Plain => broccoli::Tree::refit(&mut bots, &data),
+                Refit::Coherent => broccoli::Tree::refit_coherent(&mut bots, &data),
+                Refit::PreviousOrder => {
+                    let (mut e, v) = TreeEmbryo::with_previous_order(&mut bots, &data, &order);
+                    e.recurse_refit(v, &mut CoherentSorter);
+                    e.finish()
+                }
+            };
+            broccoli::assert::assert_refit_tree_invariants(&tree);
+
+            let mut res_tree = vec![];
+            tree.find_colliding_pairs(|a, b| {
+                let (a, b) = ((a.0).1, (b.0).1);
+                res_tree.push(if a < b { (a, b) } else { (b, a) });
+            });
+            data = tree.get_tree_data();
+
+            let mut res_naive = vec![];
+            Naive::new(&mut bots).find_colliding_pairs(|a, b| {
+                let (a, b) = ((a.0).1, (b.0).1);
+                res_naive.push(if a < b { (a, b) } else { (b, a) });
+            });
+
+            res_tree.sort_unstable();
+            res_naive.sort_unstable();
+            assert_eq!(res_tree, res_naive);
+        }
+    }
+
+    for &num_bots in [0, 20, 40, 10000].iter() {
+        check(num_bots, Refit::Plain);
+        check(num_bots, Refit::Coherent);
+        check(num_bots, Refit::PreviousOrder);
+    }
+}
+
+#[test]
+#[should_panic(expected = "order is not a permutation")]
+fn test_previous_order_out_of_range() {
+    use broccoli::build::TreeEmbryo;
+
+    let mut bots = [axgeom::rect(0, 10, 0, 10); 3];
+    let data = broccoli::Tree::new(&mut bots).get_tree_data();
+    let _ = TreeEmbryo::with_previous_order(&mut bots, &data, &[0, 1, 3]);
+}
+
+#[test]
+fn test_coherent_sorter_comparisons() {
+    use broccoli::aabb::Aabb;
+    use broccoli::build::{CoherentSorter, Sorter};
+    use std::cell::Cell;
+
+    //Counts how many times its aabb is looked at.
+    struct Counted<'a> {
+        rect: axgeom::Rect<i64>,
+        num: &'a Cell<usize>,
+    }
+
+    impl Aabb for Counted<'_> {
+        type Num = i64;
+        fn get(&self) -> &axgeom::Rect<i64> {
+            self.num.set(self.num.get() + 1);
+            &self.rect
+        }
+    }
+
+    let n = 10000;
+
+    //Every comparison looks at two aabbs. A sort that did not make use of
+    //the bots being nearly sorted would make about n*log2(n) comparisons.
+    let check = |max_per_bot: usize, shuffle: &dyn Fn(&mut [Counted])| {
+        let num = Cell::new(0);
+        let mut bots: Vec<_> = (0..n as i64)
+            .map(|i| Counted {
+                rect: axgeom::rect(i, i + 5, 0, 5),
+                num: &num,
+            })
+            .collect();
+        shuffle(&mut bots);
+        num.set(0);
+
+        CoherentSorter.sort(axgeom::XAXIS, &mut bots);
+        assert!(bots
+            .windows(2)
+            .all(|w| w[0].rect.x.start <= w[1].rect.x.start));
+        assert!(
+            num.get() <= max_per_bot * n,
+            "{} aabbs looked at",
+            num.get()
+        );
+    };
+
+    //Already sorted.
+    check(6, &|_| {});
+
+    //A few bots moved past their neighbour since the last frame.
+    check(6, &|bots| {
+        for i in (0..n - 1).step_by(1000) {
+            bots.swap(i, i + 1);
+        }
+    });
+
+    //One bot moved all the way across.
+    check(6, &|bots| bots.rotate_right(1));
+    check(6, &|bots| bots.rotate_left(1));
+
+    //Half of the bots moved past the other half. The insertion sort
+    //gives up and the two sorted runs are merged instead.
+    check(8, &|bots| bots.rotate_left(n / 2));
+}
+
+#[test]
+fn test_divider_strategies() {
+    use broccoli::aabb::ManySwappable;
+    use broccoli::assert::Naive;
+    use broccoli::build::*;
+
+    fn check<D: DividerStrategy<ManySwappable<(axgeom::Rect<i64>, usize)>>>(
+        mut bots: Vec<ManySwappable<(axgeom::Rect<i64>, usize)>>,
+        divider: D,
+        div_is_a_start: bool,
+    ) {
+        let mut tree = broccoli::Tree::new_with_divider(&mut bots, divider);
+        if div_is_a_start {
+            broccoli::assert::assert_tree_invariants(&tree);
+        } else {
+            broccoli::assert::assert_refit_tree_invariants(&tree);
+        }
+
+        let mut res_tree = vec![];
+        tree.find_colliding_pairs(|a, b| {
+            let (a, b) = ((a.0).1, (b.0).1);
+            res_tree.push(if a < b { (a, b) } else { (b, a) });
+        });
+
+        let mut res_naive = vec![];
+        Naive::new(&mut bots).find_colliding_pairs(|a, b| {
+            let (a, b) = ((a.0).1, (b.0).1);
+            res_naive.push(if a < b { (a, b) } else { (b, a) });
+        });
+
+        res_tree.sort_unstable();
+        res_naive.sort_unstable();
+        assert_eq!(res_tree, res_naive);
+    }
+
+    for &num_bots in [0, 1, 20, 40, 10000].iter() {
+        let s = dists::spiral_iter([400.0, 400.0], 4.0, 1.0);
+
+        //Put a dense cluster of big aabbs in one corner.
+        let bots: Vec<_> = s
+            .take(num_bots)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                let r = if i % 3 == 0 {
+                    let k = (i % 50) as i64;
+                    axgeom::Rect::from_point(axgeom::vec2(k, k), axgeom::vec2same(20))
+                } else {
+                    axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8))
+                };
+                ManySwappable((r, i))
+            })
+            .collect();
+
+        check(bots.clone(), MedianDivider, true);
+        check(bots.clone(), SahDivider, true);
+        check(bots.clone(), HistogramDivider::default(), false);
+        check(bots, HistogramDivider { num_bins: 2 }, false);
+    }
+}
+
+#[test]
+fn test_split_by_extent() {
+    use axgeom::AxisDyn;
+    use broccoli::aabb::ManySwappable;
+    use broccoli::assert::Naive;
+    use broccoli::queries::knearest::AabbKnearest;
+    for &num_bots in [0, 1, 20, 40, 10000].iter() {
+        //A long and thin world along the y axis.
+        let mut bots: Vec<_> = (0..num_bots)
+            .map(|i| {
+                let x = ((i * 37) % 100) as i64;
+                let y = (i * 30) as i64;
+                ManySwappable((axgeom::rect(x, x + 8, y, y + 40), i))
+            })
+            .collect();
+
+        let mut tree = broccoli::Tree::new_by_extent(&mut bots);
+        broccoli::assert::assert_tree_invariants(&tree);
+
+        if tree.num_levels() > 1 {
+            let (n, _) = compt::Visitor::next(tree.vistr());
+            assert!(matches!(n.axis, AxisDyn::Y));
+        }
+
+        let mut res_tree = vec![];
+        tree.find_colliding_pairs(|a, b| {
+            let (a, b) = ((a.0).1, (b.0).1);
+            res_tree.push(if a < b { (a, b) } else { (b, a) });
+        });
+
+        let mut rect = axgeom::rect(20, 60, 1000, 4000);
+        let mut rect_tree = vec![];
+        tree.find_all_intersect_rect(broccoli::aabb::pin::AabbPin::new(&mut rect), |_, a| {
+            rect_tree.push((a.0).1)
+        });
+
+        let mut knear_tree: Vec<_> = tree
+            .find_knearest(axgeom::vec2(30, 9000), 5, AabbKnearest)
+            .into_vec()
+            .into_iter()
+            .map(|a| ((a.bot.0).1, a.mag))
+            .collect();
+
+        //The axis of each node should survive a round trip through the tree data.
+        let data = tree.get_tree_data();
+        let tree = broccoli::Tree::from_tree_data(&mut bots, &data);
+        broccoli::assert::assert_tree_invariants(&tree);
+        let tree = broccoli::Tree::refit(&mut bots, &data);
+        broccoli::assert::assert_refit_tree_invariants(&tree);
+
+        let mut res_naive = vec![];
+        let mut naive = Naive::new(&mut bots);
+        naive.find_colliding_pairs(|a, b| {
+            let (a, b) = ((a.0).1, (b.0).1);
+            res_naive.push(if a < b { (a, b) } else { (b, a) });
+        });
+
+        let mut rect_naive = vec![];
+        naive.find_all_intersect_rect(broccoli::aabb::pin::AabbPin::new(&mut rect), |_, a| {
+            rect_naive.push((a.0).1)
+        });
+
+        let mut knear_naive: Vec<_> = naive
+            .find_knearest(axgeom::vec2(30, 9000), 5, AabbKnearest)
+            .into_vec()
+            .into_iter()
+            .map(|a| ((a.bot.0).1, a.mag))
+            .collect();
+
+        res_tree.sort_unstable();
+        res_naive.sort_unstable();
+        assert_eq!(res_tree, res_naive);
+
+        rect_tree.sort_unstable();
+        rect_naive.sort_unstable();
+        assert_eq!(rect_tree, rect_naive);
+
+        knear_tree.sort_unstable();
+        knear_naive.sort_unstable();
+        assert_eq!(knear_tree, knear_naive);
+    }
+}
+
+#[test]
+fn test_tree3d() {
+    use broccoli::assert::Assert3;
+    use broccoli::tree3d::knearest::AabbKnearest3;
+    use broccoli::tree3d::raycast::{AabbRaycast3, Ray3};
+    use broccoli::tree3d::*;
+
+    for &num_bots in [0, 1, 20, 40, 3000].iter() {
+        let s = dists::spiral_iter([400.0, 400.0], 4.0, 1.0);
+
+        let mut bots: Vec<_> = s
+            .take(num_bots)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                let (x, y, z) = (x as i64, y as i64, (i as i64 * 37) % 400);
+                let len = if i % 50 == 0 { 60 } else { 8 };
+                (
+                    rect3(x - len, x + len, y - len, y + len, z - len, z + len),
+                    0usize,
+                )
+            })
+            .collect();
+
+        let mut tree = Tree3::new(&mut bots);
+        broccoli::assert::assert_tree3_invariants(&tree);
+        tree.find_colliding_pairs(|a, b| {
+            *a.unpack_inner3() += 1;
+            *b.unpack_inner3() += 1;
+        });
+        let num_pairs: usize = bots.iter().map(|b| b.1).sum::<usize>() / 2;
+
+        let mut naive_pairs = 0;
+        broccoli::assert::Naive3::new(&mut bots).find_colliding_pairs(|_, _| naive_pairs += 1);
+        assert_eq!(num_pairs, naive_pairs);
+
+        let mut a = Assert3::new(&mut bots);
+        a.assert_query();
+        a.assert_rect(rect3(300, 450, 350, 500, 0, 200));
+        a.assert_rect(rect3(-100, 1000, 390, 410, 100, 110));
+        a.assert_k_nearest_mut(vec3(400, 400, 200), 5, AabbKnearest3);
+        a.assert_k_nearest_mut(vec3(-100, 300, 900), 3, AabbKnearest3);
+        a.assert_raycast(
+            Ray3 {
+                point: vec3(-50, 400, 200),
+                dir: vec3(1, 0, 0),
+            },
+            AabbRaycast3,
+        );
+        a.assert_raycast(
+            Ray3 {
+                point: vec3(400, 400, -100),
+                dir: vec3(1, 1, 2),
+            },
+            AabbRaycast3,
+        );
+    }
+}
+
+#[test]
+fn test_colliding_pairs_with() {
+    use broccoli::assert::{Assert, Naive};
+    for &(num_a, num_b) in [(0, 0), (0, 40), (20, 40), (1000, 30), (3000, 5000)].iter() {
+        let mut a: Vec<_> = dists::spiral_iter([400.0, 400.0], 4.0, 1.0)
+            .take(num_a)
+            .map(|[x, y]| {
+                axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2same(8))
+            })
+            .collect();
+
+        //Spread out differently so the two trees have different dividers.
+        let mut b: Vec<_> = dists::spiral_iter([350.0, 420.0], 6.0, 1.5)
+            .take(num_b)
+            .enumerate()
+            .map(|(i, [x, y])| {
+                let len = if i % 50 == 0 { 60 } else { 5 };
+                axgeom::Rect::from_point(axgeom::vec2(x as i64, y as i64), axgeom::vec2(len, 5))
+            })
+            .collect();
+
+        Assert::new(&mut a).assert_colliding_pairs_with(&mut b);
+        Assert::new(&mut b).assert_colliding_pairs_with(&mut a);
+
+        //Trees whose axes do not alternate every level.
+        let mut res = vec![];
+        broccoli::Tree::new_by_extent(&mut a)
+            .find_colliding_pairs_with(&mut broccoli::Tree::new_by_extent(&mut b), |a, b| {
+                res.push((*a, *b))
+            });
+
+        let mut expected = vec![];
+        Naive::new(&mut a)
+            .find_colliding_pairs_with(&mut Naive::new(&mut b), |a, b| expected.push((*a, *b)));
+
+        let key = |(a, b): &(axgeom::Rect<i64>, axgeom::Rect<i64>)| {
+            [
+                a.x.start, a.x.end, a.y.start, a.y.end, b.x.start, b.x.end, b.y.start, b.y.end,
+            ]
+        };
+        res.sort_by_key(key);
+        expected.sort_by_key(key);
+        assert_eq!(res, expected);
+    }
+}
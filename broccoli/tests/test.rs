@@ -158,3 +158,910 @@ fn test_tie_raycast() {
         }
     }
 }
+
+#[test]
+fn test_owned_tree() {
+    use broccoli::assert::Naive;
+    use broccoli::owned::OwnedTree;
+
+    fn check(tree: &mut OwnedTree<(Rect<isize>, usize)>) {
+        let mut res_tree = vec![];
+        tree.as_tree().find_colliding_pairs(|a, b| {
+            let (a, b) = (a.inner().1, b.inner().1);
+            res_tree.push(if a < b { (a, b) } else { (b, a) });
+        });
+        broccoli::assert::assert_refit_tree_invariants(&tree.as_tree());
+
+        let mut bots: Vec<_> = tree.iter().map(|(_, b)| *b).collect();
+        let mut res_naive = vec![];
+        Naive::new(&mut bots).find_colliding_pairs(|a, b| {
+            let (a, b) = (a.1, b.1);
+            res_naive.push(if a < b { (a, b) } else { (b, a) });
+        });
+
+        res_tree.sort_unstable();
+        res_naive.sort_unstable();
+        assert_eq!(res_tree, res_naive);
+    }
+
+    let bots = (0..500)
+        .map(|i| {
+            let x = (i * 37 % 400) as isize;
+            let y = (i * 91 % 400) as isize;
+            (rect(x, x + 10, y, y + 10), i)
+        })
+        .collect();
+
+    let mut tree = OwnedTree::new(bots);
+    check(&mut tree);
+
+    let mut handles: Vec<_> = tree.iter().map(|(h, _)| h).collect();
+    for h in handles.drain(..250) {
+        assert!(tree.remove(h).is_some());
+        assert!(tree.remove(h).is_none());
+        assert!(tree.get(h).is_none());
+    }
+    assert_eq!(tree.len(), 250);
+    check(&mut tree);
+
+    for i in 500..2000 {
+        let x = (i * 13 % 100) as isize;
+        handles.push(tree.insert((rect(x, x + 5, x, x + 5), i)));
+    }
+    check(&mut tree);
+
+    for (_, b) in tree.iter_mut() {
+        b.0.x.start += 3;
+        b.0.x.end += 3;
+    }
+    check(&mut tree);
+
+    let h = handles[300];
+    tree.get_mut(h).unwrap().0 = rect(1000, 1010, 1000, 1010);
+    assert_eq!(tree.get(h).unwrap().0, rect(1000, 1010, 1000, 1010));
+    check(&mut tree);
+}
+
+#[test]
+fn test_try_new() {
+    use broccoli::build::TreeError;
+
+    let mut bots = vec![
+        (rect(0.0f32, 10.0, 0.0, 10.0), 0usize),
+        (rect(5.0, 15.0, 5.0, 15.0), 1),
+        (rect(20.0, 30.0, 20.0, 30.0), 2),
+    ];
+
+    let data = {
+        let mut tree = broccoli::Tree::try_new(&mut bots).unwrap();
+        let mut num = 0;
+        tree.find_colliding_pairs(|_, _| num += 1);
+        assert_eq!(num, 1);
+        tree.get_tree_data()
+    };
+
+    assert!(broccoli::Tree::try_from_tree_data(&mut bots, &data).is_ok());
+    assert_eq!(
+        broccoli::Tree::try_from_tree_data(&mut bots[..2], &data).err(),
+        Some(TreeError::LengthMismatch {
+            expected: 3,
+            found: 2
+        })
+    );
+
+    bots[1].0.y.end = f32::NAN;
+    assert_eq!(
+        broccoli::Tree::try_new(&mut bots).err(),
+        Some(TreeError::NanBound { index: 1 })
+    );
+    assert_eq!(
+        broccoli::Tree::try_from_tree_data(&mut bots, &data).err(),
+        Some(TreeError::NanBound { index: 1 })
+    );
+
+    bots[1].0.y.end = 15.0;
+    bots[2].0.x = Range::new(30.0, 20.0);
+    assert_eq!(
+        broccoli::Tree::try_new(&mut bots).err(),
+        Some(TreeError::InvertedRange { index: 2 })
+    );
+    assert!(broccoli::build::TreeEmbryo::try_new(&mut bots).is_err());
+}
+
+fn tree_data_pairs(
+    bots: &mut [(Rect<i32>, usize)],
+    data: &broccoli::TreeData<i32>,
+) -> Vec<(usize, usize)> {
+    let mut tree = broccoli::Tree::from_tree_data(bots, data);
+    broccoli::assert::assert_tree_invariants(&tree);
+    sorted_pairs(&mut tree)
+}
+
+///Square rects of the specified size placed along a spiral that grows out from the center.
+fn spiral_rects(center: [f64; 2], grow: f64, size: i32) -> impl Iterator<Item = Rect<i32>> {
+    dists::spiral_iter(center, grow, 1.0).map(move |[x, y]| {
+        let (x, y) = (x as i32, y as i32);
+        rect(x, x + size, y, y + size)
+    })
+}
+
+///The colliding pairs of the tree, by the index stored with each element.
+fn sorted_pairs<N: Num>(tree: &mut broccoli::Tree<(Rect<N>, usize)>) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    tree.find_colliding_pairs(|a, b| {
+        let (a, b) = (*a.unpack_inner(), *b.unpack_inner());
+        pairs.push((a.min(b), a.max(b)));
+    });
+    pairs.sort_unstable();
+    pairs
+}
+
+#[test]
+fn test_encode_tree_data() {
+    use broccoli::encode::DecodeError;
+    use broccoli::TreeData;
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12)
+        .take(2000)
+        .zip(0..)
+        .collect();
+
+    let data = broccoli::Tree::new(&mut bots).get_tree_data();
+    let expected = tree_data_pairs(&mut bots, &data);
+
+    let path = std::env::temp_dir().join(format!("broccoli_tree_{}.bin", std::process::id()));
+    std::fs::write(&path, data.to_bytes()).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let data2 = TreeData::<i32>::from_bytes(&bytes).unwrap();
+    assert_eq!(data2.to_bytes(), bytes);
+    assert!(broccoli::Tree::try_from_tree_data(&mut bots, &data2).is_ok());
+    assert_eq!(tree_data_pairs(&mut bots, &data2), expected);
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert_eq!(
+        TreeData::<i32>::from_bytes(&bad).err(),
+        Some(DecodeError::BadMagic)
+    );
+
+    let mut bad = bytes.clone();
+    bad[4] = 200;
+    assert_eq!(
+        TreeData::<i32>::from_bytes(&bad).err(),
+        Some(DecodeError::UnsupportedVersion(200))
+    );
+
+    assert_eq!(
+        TreeData::<i64>::from_bytes(&bytes).err(),
+        Some(DecodeError::NumSizeMismatch {
+            expected: 8,
+            found: 4
+        })
+    );
+
+    assert_eq!(
+        TreeData::<i32>::from_bytes(&bytes[..bytes.len() - 1]).err(),
+        Some(DecodeError::UnexpectedEnd)
+    );
+
+    let mut bad = bytes.clone();
+    bad.push(0);
+    assert_eq!(
+        TreeData::<i32>::from_bytes(&bad).err(),
+        Some(DecodeError::TrailingBytes)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_tree_data() {
+    let mut bots: Vec<_> = (0..100)
+        .map(|i| {
+            let x = (i % 10) * 10;
+            let y = (i / 10) * 10;
+            (rect(x, x + 12, y, y + 12), i as usize)
+        })
+        .collect();
+
+    let data = broccoli::Tree::new(&mut bots).get_tree_data();
+    let expected = tree_data_pairs(&mut bots, &data);
+
+    let s = serde_json::to_string(&data).unwrap();
+    let data2: broccoli::TreeData<i32> = serde_json::from_str(&s).unwrap();
+    assert_eq!(data2.to_bytes(), data.to_bytes());
+    assert_eq!(tree_data_pairs(&mut bots, &data2), expected);
+}
+
+#[test]
+fn test_radix_sorter() {
+    use broccoli::build::{RadixKey, RadixSorter};
+
+    fn check<N: RadixKey>(bots: &mut [(Rect<N>, usize)]) {
+        let expected = sorted_pairs(&mut broccoli::Tree::new(bots));
+
+        let mut tree = broccoli::Tree::new_with_sorter(bots, RadixSorter);
+        broccoli::assert::assert_tree_invariants(&tree);
+        assert_eq!(sorted_pairs(&mut tree), expected);
+    }
+
+    let mut bots: Vec<_> = spiral_rects([0.0, 0.0], 4.0, 12)
+        .take(2000)
+        .zip(0..)
+        .collect();
+    assert!(bots.iter().any(|a| a.0.x.start < 0 && a.0.y.start < 0));
+    check(&mut bots);
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12)
+        .take(2000)
+        .map(|r| {
+            rect(
+                r.x.start as u16,
+                r.x.end as u16,
+                r.y.start as u16,
+                r.y.end as u16,
+            )
+        })
+        .zip(0..)
+        .collect();
+    check(&mut bots);
+}
+
+#[test]
+fn test_stats() {
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12).take(2000).collect();
+
+    let mut tree = broccoli::Tree::new(&mut bots);
+    let stats = tree.stats();
+
+    assert_eq!(stats.num_levels(), tree.num_levels());
+    assert_eq!(stats.num_elems(), 2000);
+    assert_eq!(stats.leaf_sizes.len(), 1 << (tree.num_levels() - 1));
+    assert_eq!(
+        stats.leaf_sizes.len() + stats.internal_sizes.len(),
+        tree.num_nodes()
+    );
+    assert_eq!(
+        stats.leaf_sizes.iter().sum::<usize>() + stats.internal_sizes.iter().sum::<usize>(),
+        2000
+    );
+    assert_eq!(stats.num_empty_nodes, 0);
+    assert!(stats.mean_cont_width > 0.0);
+    assert!(stats.max_cont_width >= stats.mean_cont_width);
+
+    let mut num_pairs = 0;
+    tree.find_colliding_pairs(|_, _| num_pairs += 1);
+    assert!(num_pairs > 0);
+    assert!(stats.colfind_cost >= num_pairs);
+    assert!(stats.colfind_cost < 2000 * 1999 / 2);
+
+    //Every element is in the root, so everything is checked against everything.
+    let mut bots: Vec<_> = (0..100usize).map(|i| (rect(0, 10, 0, 10), i)).collect();
+    let (mut e, v) = broccoli::build::TreeEmbryo::with_num_level(&mut bots, 3);
+    e.recurse(v, &mut broccoli::build::DefaultSorter);
+    let stats = e.finish().stats();
+    assert_eq!(stats.level_elems, vec![100, 0, 0]);
+    assert_eq!(stats.colfind_cost, 100 * 99 / 2);
+    assert_eq!(stats.num_empty_nodes, 2);
+    assert_eq!(stats.max_cont_width, 10.0);
+}
+
+#[test]
+fn test_colliding_pair_indices() {
+    use broccoli::indexed::IndexedTree;
+
+    let bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12).take(2000).collect();
+
+    Assert::new(&mut bots.clone()).assert_colliding_pair_indices();
+
+    let mut moved = bots.clone();
+    let tree = IndexedTree::new(&mut moved);
+    broccoli::assert::assert_tree_invariants(tree.as_tree());
+
+    let positions = tree.as_tree().positions();
+    let elems = tree
+        .as_tree()
+        .get_nodes()
+        .iter()
+        .flat_map(|n| n.range.iter());
+    for (i, a) in elems.enumerate() {
+        assert_eq!(positions.of(a), Some(i));
+    }
+    assert_eq!(positions.of(&bots[0]), None);
+
+    let perm = tree.get_permutation().to_vec();
+    for (i, &p) in perm.iter().enumerate() {
+        assert_eq!(moved[i], bots[p]);
+    }
+}
+
+#[test]
+fn test_collision_filter() {
+    use broccoli::aabb::ManySwappable;
+    use broccoli::assert::Naive;
+    use broccoli::queries::colfind::filter::{BitMaskFilter, CollisionMask};
+
+    //Bots that are near each other share a layer, so whole subtrees get skipped.
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12)
+        .take(2000)
+        .enumerate()
+        .map(|(i, r)| {
+            let layer = 1 << (i / 250);
+            let mask = CollisionMask::new(layer, layer | 1).with_group((i % 5) as u32);
+            ManySwappable((r, mask))
+        })
+        .collect();
+
+    let filter = BitMaskFilter(|a: &ManySwappable<(Rect<i32>, CollisionMask)>| a.0 .1);
+
+    let mut num = 0;
+    Naive::new(&mut bots).find_colliding_pairs_filtered(filter, |_, _| num += 1);
+    assert!(num > 0);
+
+    Assert::new(&mut bots).assert_colliding_pairs_filtered(filter);
+}
+
+#[test]
+fn test_try_find_colliding_pairs() {
+    use core::ops::ControlFlow;
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12).take(2000).collect();
+
+    let mut tree = broccoli::Tree::new(&mut bots);
+
+    let mut total = 0;
+    tree.find_colliding_pairs(|_, _| total += 1);
+    assert!(total > 100);
+
+    let mut num = 0;
+    let res = tree.try_find_colliding_pairs(|_, _| {
+        num += 1;
+        ControlFlow::<()>::Continue(())
+    });
+    assert_eq!(res, ControlFlow::Continue(()));
+    assert_eq!(num, total);
+
+    for stop in [1, 2, 50, total] {
+        let mut num = 0;
+        let res = tree.try_find_colliding_pairs(|_, _| {
+            num += 1;
+            if num == stop {
+                ControlFlow::Break(num)
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(res, ControlFlow::Break(stop));
+        assert_eq!(num, stop);
+    }
+}
+
+#[test]
+fn test_colliding_pairs_chunks() {
+    fn ptr<T>(a: &T) -> usize {
+        a as *const T as usize
+    }
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12).take(2000).collect();
+
+    let mut tree = broccoli::Tree::new(&mut bots);
+
+    let mut expected = vec![];
+    tree.find_colliding_pairs(|a, b| expected.push((ptr(&*a), ptr(&*b))));
+    assert!(expected.len() > 100);
+
+    let mut found = vec![];
+    let mut num_chunks = 0;
+    let mut pairs = tree.colliding_pairs();
+    while let Some(mut chunk) = pairs.next_chunk() {
+        assert!(!chunk.is_empty());
+        num_chunks += 1;
+        while let Some((a, b)) = chunk.next_pair() {
+            assert!(a.intersects_rect(&b));
+            found.push((ptr(&*a), ptr(&*b)));
+        }
+        assert_eq!(chunk.len(), 0);
+    }
+    assert!(pairs.next_chunk().is_none());
+
+    assert!(num_chunks > 1);
+    assert_eq!(found, expected);
+
+    //Stopping early leaves the tree usable.
+    let mut pairs = tree.colliding_pairs();
+    let mut chunk = pairs.next_chunk().unwrap();
+    chunk.next_pair().unwrap();
+
+    let mut num = 0;
+    tree.find_colliding_pairs(|_, _| num += 1);
+    assert_eq!(num, expected.len());
+}
+
+#[test]
+fn test_half_open() {
+    //A grid of tiles that share edges with their neighbours.
+    let mut tiles: Vec<_> = (0..40)
+        .flat_map(|x| (0..40).map(move |y| rect(x * 10, x * 10 + 10, y * 10, y * 10 + 10)))
+        .collect();
+
+    let mut num = 0;
+    broccoli::Tree::new(&mut tiles).find_colliding_pairs(|_, _| num += 1);
+    //Every tile touches the tiles around it, including the diagonal ones.
+    assert_eq!(num, 2 * 40 * 39 + 2 * 39 * 39);
+
+    let mut half_open: Vec<_> = tiles.iter().copied().map(HalfOpen).collect();
+    let mut num = 0;
+    broccoli::Tree::new(&mut half_open).find_colliding_pairs(|_, _| num += 1);
+    assert_eq!(num, 0);
+
+    let mut num = 0;
+    broccoli::Tree::new(&mut half_open)
+        .find_all_intersect_rect(pin::AabbPin::new(&mut rect(10, 30, 10, 30)), |_, _| {
+            num += 1
+        });
+    assert_eq!(num, 4);
+
+    //Overlapping and empty rects as well.
+    tiles.extend([
+        rect(5, 15, 5, 15),
+        rect(20, 20, 0, 40),
+        rect(0, 400, 100, 100),
+    ]);
+    Assert::new(&mut tiles).assert_query();
+    Assert::new(&mut tiles).assert_rect(rect(10, 30, 10, 30));
+}
+
+#[test]
+fn test_sap() {
+    use broccoli::assert::Naive;
+    use broccoli::queries::sap::SweepAndPrune;
+
+    fn check<T: Aabb<Num = i32> + Clone>(
+        sap: &mut SweepAndPrune<T>,
+        index: impl Fn(&T) -> usize + Copy,
+    ) {
+        let mut bots = sap.get_elements().to_vec();
+
+        let mut expected = vec![];
+        Naive::new(&mut bots).find_colliding_pairs(|a, b| {
+            let (a, b) = (index(&a), index(&b));
+            expected.push((a.min(b), a.max(b)));
+        });
+        expected.sort_unstable();
+
+        let mut found = vec![];
+        sap.find_colliding_pairs(|a, b| found.push((index(&a), index(&b))));
+        assert_eq!(found, expected);
+        assert_eq!(sap.num_pairs(), expected.len());
+
+        let mut r = rect(100, 300, 50, 250);
+
+        let mut expected = vec![];
+        Naive::new(&mut bots)
+            .find_all_intersect_rect(pin::AabbPin::new(&mut r), |_, a| expected.push(index(&a)));
+        let mut found = vec![];
+        sap.find_all_intersect_rect(pin::AabbPin::new(&mut r), |_, a| found.push(index(&a)));
+        assert_eq!(found, expected);
+
+        let mut expected = vec![];
+        Naive::new(&mut bots)
+            .find_all_in_rect(pin::AabbPin::new(&mut r), |_, a| expected.push(index(&a)));
+        let mut found = vec![];
+        sap.find_all_in_rect(pin::AabbPin::new(&mut r), |_, a| found.push(index(&a)));
+        assert_eq!(found, expected);
+
+        let mut expected = vec![];
+        Naive::new(&mut bots)
+            .find_all_not_in_rect(pin::AabbPin::new(&mut r), |_, a| expected.push(index(&a)));
+        let mut found = vec![];
+        sap.find_all_not_in_rect(pin::AabbPin::new(&mut r), |_, a| found.push(index(&a)));
+        assert_eq!(found, expected);
+    }
+
+    let mut bots: Vec<_> = spiral_rects([200.0, 200.0], 4.0, 12)
+        .take(300)
+        .zip(0..)
+        .collect();
+
+    let mut half_open: Vec<_> = bots.iter().copied().map(HalfOpen).collect();
+
+    let mut sap = SweepAndPrune::new(&mut bots);
+    let mut sap_half_open = SweepAndPrune::new(&mut half_open);
+
+    for frame in 0..20 {
+        check(&mut sap, |a| a.1);
+        check(&mut sap_half_open, |a| a.0 .1);
+
+        for (r, i) in sap.get_elements_mut() {
+            let (dx, dy) = ((*i % 7) as i32 - 3, (*i % 5) as i32 - 2);
+            *r = rect(r.x.start + dx, r.x.end + dx, r.y.start + dy, r.y.end + dy);
+
+            //Some elements jump far away and come back.
+            if *i % 50 == frame {
+                *r = rect(r.x.start + 500, r.x.end + 500, r.y.start, r.y.end);
+            } else if *i % 50 + 1 == frame {
+                *r = rect(r.x.start - 500, r.x.end - 500, r.y.start, r.y.end);
+            }
+        }
+        let moved: Vec<_> = sap.get_elements().to_vec();
+        for (a, b) in sap_half_open.get_elements_mut().iter_mut().zip(moved) {
+            a.0 = b;
+        }
+    }
+
+    //Tiles that share edges only collide while closed.
+    let mut tiles: Vec<_> = (0..10)
+        .flat_map(|x| (0..10).map(move |y| rect(x * 10, x * 10 + 10, y * 10, y * 10 + 10)))
+        .collect();
+    let mut half_open: Vec<_> = tiles.iter().copied().map(HalfOpen).collect();
+    assert_eq!(
+        SweepAndPrune::new(&mut tiles).num_pairs(),
+        2 * 10 * 9 + 2 * 9 * 9
+    );
+
+    let mut sap = SweepAndPrune::new(&mut half_open);
+    assert_eq!(sap.num_pairs(), 0);
+    sap.get_elements_mut()[0] = HalfOpen(rect(1, 11, 1, 11));
+    assert_eq!(sap.num_pairs(), 3);
+}
+
+#[test]
+fn test_find_impacts() {
+    use broccoli::queries::ccd::{
+        find_impacts, find_impacts_closure, AabbTimeOfImpact, Impact, TimeOfImpact,
+    };
+
+    //Walls that stay put and bullets that move far every frame.
+    let mut bots: Vec<_> = dists::spiral_iter([400.0, 400.0], 20.0, 1.0)
+        .take(400)
+        .enumerate()
+        .map(|(i, [x, y])| {
+            let (vx, vy) = if i % 4 == 0 {
+                (0.0, 0.0)
+            } else {
+                ((i % 13) as f64 * 10.0 - 60.0, (i % 7) as f64 * 10.0 - 30.0)
+            };
+            let cur = rect(x, x + 3.0, y, y + 3.0);
+            let prev = rect(x - vx, x + 3.0 - vx, y - vy, y + 3.0 - vy);
+            (cur, prev)
+        })
+        .collect();
+
+    let prev = |a: &(Rect<f64>, Rect<f64>)| a.1;
+
+    let mut expected = vec![];
+    let mut toi = AabbTimeOfImpact(prev);
+    for i in 0..bots.len() {
+        for j in i + 1..bots.len() {
+            let (left, right) = bots.split_at_mut(j);
+            let (a, b) = (
+                pin::AabbPin::from_mut(&mut left[i]),
+                pin::AabbPin::from_mut(&mut right[0]),
+            );
+            if let Some(time) = toi.time_of_impact(a, b) {
+                assert!((0.0..=1.0).contains(&time));
+                expected.push(Impact { a: i, b: j, time });
+            }
+        }
+    }
+    expected.sort_by(|x, y| {
+        x.time
+            .partial_cmp(&y.time)
+            .unwrap()
+            .then((x.a, x.b).cmp(&(y.a, y.b)))
+    });
+
+    let mut num = 0;
+    let mut current: Vec<_> = bots.iter().map(|a| a.0).collect();
+    broccoli::Tree::new(&mut current).find_colliding_pairs(|_, _| num += 1);
+    assert!(expected.len() > num);
+
+    assert_eq!(find_impacts(&mut bots, AabbTimeOfImpact(prev)), expected);
+
+    let mut num_calls = 0;
+    let impacts = find_impacts_closure(&mut bots, prev, |a, b| {
+        num_calls += 1;
+        toi.time_of_impact(a, b)
+    });
+    assert_eq!(impacts, expected);
+    assert!(num_calls < 400 * 399 / 2);
+}
+
+#[test]
+fn test_scene() {
+    use broccoli::assert::Naive;
+    use broccoli::scene::Scene;
+
+    //Static elements that overlap each other a lot.
+    let statics: Vec<_> = spiral_rects([400.0, 400.0], 8.0, 20)
+        .take(1000)
+        .zip(0..)
+        .map(ManySwappable)
+        .collect();
+
+    let mut scene = Scene::new(statics);
+
+    let mut num_static = 0;
+    Naive::new(&mut scene.statics().to_vec()).find_colliding_pairs(|_, _| num_static += 1);
+    assert!(num_static > 0);
+
+    for frame in 0..5 {
+        let mut dynamics: Vec<_> = spiral_rects([300.0, 300.0], 12.0, 10)
+            .take(500)
+            .map(|r| {
+                rect(
+                    r.x.start + frame * 30,
+                    r.x.end + frame * 30,
+                    r.y.start,
+                    r.y.end,
+                )
+            })
+            .zip(0..)
+            .map(ManySwappable)
+            .collect();
+
+        let mut expected_dynamic = vec![];
+        Naive::new(&mut dynamics.clone()).find_colliding_pairs(|a, b| {
+            expected_dynamic.push((a.0 .1.min(b.0 .1), a.0 .1.max(b.0 .1)))
+        });
+        let mut expected_static = vec![];
+        let mut statics = scene.statics().to_vec();
+        Naive::new(&mut dynamics.clone())
+            .find_colliding_pairs_with(&mut Naive::new(&mut statics), |a, b| {
+                expected_static.push((a.0 .1, b.0 .1))
+            });
+
+        let mut found_dynamic = vec![];
+        let mut found_static = vec![];
+        scene.find_colliding_pairs(
+            &mut dynamics,
+            |a, b| found_dynamic.push((a.0 .1.min(b.0 .1), a.0 .1.max(b.0 .1))),
+            |a, b| found_static.push((a.0 .1, b.0 .1)),
+        );
+
+        for v in [
+            &mut expected_dynamic,
+            &mut expected_static,
+            &mut found_dynamic,
+            &mut found_static,
+        ] {
+            v.sort_unstable();
+        }
+        assert!(!found_static.is_empty());
+        assert_eq!(found_dynamic, expected_dynamic);
+        assert_eq!(found_static, expected_static);
+    }
+
+    let mut num = 0;
+    scene.static_tree().find_colliding_pairs(|_, _| num += 1);
+    assert_eq!(num, num_static);
+    assert_eq!(scene.into_statics().len(), 1000);
+}
+
+#[test]
+fn test_read_only() {
+    use broccoli::queries::knearest::AabbKnearest;
+    use broccoli::queries::raycast::AabbRaycast;
+
+    fn ptr<T>(a: &T) -> usize {
+        a as *const T as usize
+    }
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 12).take(2000).collect();
+
+    let mut tree = broccoli::Tree::new(&mut bots);
+
+    let r = rect(300, 420, 350, 500);
+    let point = vec2(401, 399);
+    let ray = axgeom::Ray {
+        point: vec2(0, 410),
+        dir: vec2(1, 0),
+    };
+
+    let mut in_rect = vec![];
+    tree.find_all_in_rect(pin::AabbPin::new(&mut r.clone()), |_, a| {
+        in_rect.push(ptr(&*a))
+    });
+    let mut intersect_rect = vec![];
+    tree.find_all_intersect_rect(pin::AabbPin::new(&mut r.clone()), |_, a| {
+        intersect_rect.push(ptr(&*a))
+    });
+    let mut containing_point = vec![];
+    let pr = rect(point.x, point.x, point.y, point.y);
+    tree.find_all_intersect_rect(pin::AabbPin::new(&mut pr.clone()), |_, a| {
+        containing_point.push(ptr(&*a))
+    });
+    let knearest: Vec<_> = tree
+        .find_knearest(point, 5, AabbKnearest)
+        .into_vec()
+        .into_iter()
+        .map(|a| (ptr(&*a.bot), a.mag))
+        .collect();
+    let raycast = match tree.cast_ray(ray, AabbRaycast) {
+        CastResult::Hit(a) => {
+            let mut e: Vec<_> = a.elems.iter().map(|a| ptr(&**a)).collect();
+            e.sort_unstable();
+            Some((e, a.mag))
+        }
+        CastResult::NoHit => None,
+    };
+    assert!(!in_rect.is_empty() && !containing_point.is_empty() && raycast.is_some());
+
+    let check = |tree: broccoli::queries::TreeRef<Rect<i32>>| {
+        let mut v = vec![];
+        tree.find_all_in_rect(&r, |a| v.push(ptr(a)));
+        assert_eq!(v, in_rect);
+
+        let mut v = vec![];
+        tree.find_all_intersect_rect(&r, |a| v.push(ptr(a)));
+        assert_eq!(v, intersect_rect);
+
+        let mut v = vec![];
+        tree.find_all_containing_point(point, |a| v.push(ptr(a)));
+        assert_eq!(v, containing_point);
+
+        let v: Vec<_> = tree
+            .find_knearest(point, 5, AabbKnearest)
+            .into_iter()
+            .map(|(a, mag)| (ptr(a), mag))
+            .collect();
+        assert_eq!(v, knearest);
+
+        let v = match tree.cast_ray(ray, AabbRaycast) {
+            CastResult::Hit((elems, mag)) => {
+                let mut e: Vec<_> = elems.into_iter().map(ptr).collect();
+                e.sort_unstable();
+                Some((e, mag))
+            }
+            CastResult::NoHit => None,
+        };
+        assert_eq!(v, raycast);
+    };
+
+    let shared = tree.read_only();
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| check(shared));
+        }
+    });
+}
+
+#[test]
+fn test_find_all_in_radius() {
+    use broccoli::queries::knearest::AabbKnearest;
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 8).take(1000).collect();
+
+    for point in [vec2(400, 400), vec2(350, 430), vec2(0, 0), vec2(500, 200)] {
+        for radius in [0, 1, 10 * 10, 40 * 40, 500 * 500] {
+            Assert::new(&mut bots).assert_radius(point, radius, AabbKnearest);
+        }
+    }
+
+    let mut tree = broccoli::Tree::new(&mut bots);
+    let mut num = 0;
+    tree.find_all_in_radius(vec2(400, 400), 500 * 500, AabbKnearest, |_, _| num += 1);
+    assert_eq!(num, 1000);
+}
+
+#[test]
+fn test_find_all_intersect_convex() {
+    //Separating axis test with the normals of the polygon's edges.
+    //The axes of the rect are already covered by the bounding rect.
+    fn fine(polygon: &[Vec2<i32>], a: pin::AabbPin<&mut Rect<i32>>) -> bool {
+        let corners = [
+            vec2(a.x.start, a.y.start),
+            vec2(a.x.end, a.y.start),
+            vec2(a.x.end, a.y.end),
+            vec2(a.x.start, a.y.end),
+        ];
+        (0..polygon.len()).all(|i| {
+            let (p0, p1) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            let n = vec2(p0.y - p1.y, p1.x - p0.x);
+            let dot = |p: &Vec2<i32>| p.x * n.x + p.y * n.y;
+            let poly_max = polygon.iter().map(dot).max().unwrap();
+            let poly_min = polygon.iter().map(dot).min().unwrap();
+            let rect_max = corners.iter().map(dot).max().unwrap();
+            let rect_min = corners.iter().map(dot).min().unwrap();
+            rect_min <= poly_max && poly_min <= rect_max
+        })
+    }
+
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 8).take(1000).collect();
+
+    let polygons = [
+        vec![vec2(300, 300), vec2(500, 350), vec2(380, 480)],
+        vec![
+            vec2(400, 390),
+            vec2(410, 400),
+            vec2(400, 410),
+            vec2(390, 400),
+        ],
+        vec![vec2(0, 0), vec2(10, 0), vec2(5, 10)],
+        vec![vec2(405, 405)],
+        vec![],
+    ];
+
+    for polygon in polygons.iter() {
+        Assert::new(&mut bots).assert_intersect_convex(polygon, fine);
+    }
+
+    //The bounding rect of the triangle touches elements the triangle does not.
+    let mut tree = broccoli::Tree::new(&mut bots);
+    let (mut num, mut num_rect) = (0, 0);
+    tree.find_all_intersect_convex(&polygons[0], fine, |_| num += 1);
+    tree.find_all_intersect_rect(pin::AabbPin::new(&mut rect(300, 500, 300, 480)), |_, _| {
+        num_rect += 1
+    });
+    assert!(0 < num && num < num_rect);
+}
+
+#[test]
+fn test_find_all_containing_point() {
+    let mut bots: Vec<_> = spiral_rects([40.0, 40.0], 2.0, 4).take(300).collect();
+
+    let mut half_open: Vec<_> = bots.iter().copied().map(HalfOpen).collect();
+
+    //Every point on the grid, so plenty fall on dividers and rect borders.
+    for x in 0..80 {
+        for y in 0..80 {
+            Assert::new(&mut bots).assert_containing_point(vec2(x, y));
+            Assert::new(&mut half_open).assert_containing_point(vec2(x, y));
+        }
+    }
+
+    let mut tiles = [rect(0, 10, 0, 10), rect(10, 20, 0, 10)];
+    let mut tree = broccoli::Tree::new(&mut tiles);
+    let mut num = 0;
+    tree.find_all_containing_point(vec2(10, 5), |_| num += 1);
+    assert_eq!(num, 2);
+
+    let mut tiles = tiles.map(HalfOpen);
+    let mut tree = broccoli::Tree::new(&mut tiles);
+    let mut found = vec![];
+    tree.find_all_containing_point(vec2(10, 5), |a| found.push(*a.get()));
+    assert_eq!(found, [rect(10, 20, 0, 10)]);
+}
+
+#[test]
+fn test_count_queries() {
+    use broccoli::queries::knearest::AabbKnearest;
+
+    fn check<T: Aabb<Num = i32> + ManySwap>(bots: &mut [T], rects: &[Rect<i32>]) {
+        for r in rects {
+            Assert::new(bots).assert_count_rect(*r);
+        }
+        for (point, radius) in [(vec2(400, 400), 30 * 30), (vec2(0, 0), 500 * 500)] {
+            Assert::new(bots).assert_count_radius(point, radius, AabbKnearest);
+        }
+        Assert::new(bots).assert_query();
+    }
+
+    //Some of the elements have no size, to catch the borders of half open rects.
+    let mut bots: Vec<_> = spiral_rects([400.0, 400.0], 4.0, 0)
+        .take(3000)
+        .enumerate()
+        .map(|(i, r)| {
+            let size = (i % 3) as i32 * 6;
+            rect(r.x.start, r.x.start + size, r.y.start, r.y.start + size)
+        })
+        .collect();
+
+    let rects = [
+        rect(300, 500, 300, 500),
+        rect(0, 1000, 0, 1000),
+        rect(400, 400, 0, 1000),
+        rect(350, 420, 390, 460),
+        rect(-10, 0, -10, 0),
+        rect(398, 410, 398, 410),
+    ];
+
+    check(&mut bots, &rects);
+
+    let mut half_open: Vec<_> = bots.iter().copied().map(HalfOpen).collect();
+    check(&mut half_open, &rects);
+
+    let mut empty: [Rect<i32>; 0] = [];
+    check(&mut empty, &rects);
+}